@@ -0,0 +1,166 @@
+use super::ChannelId;
+use crate::*;
+use std::collections::{HashSet, VecDeque};
+use video::thumb_info::{child_text, find_child};
+
+/// Represents an article of a blomaga (ブロマガ).
+#[derive(Debug, Clone)]
+pub struct BlomagaArticle {
+    /// The ID number of this article. (without `ar`)
+    pub id: u64,
+    /// The title of this article.
+    pub title: String,
+    /// The URL of this article.
+    pub url: String,
+    /// The summary of this article, decorated with HTML.
+    pub description: String,
+    /// The date when this article was published.
+    pub published_at: DateTime<FixedOffset>,
+}
+impl BlomagaArticle {
+    /// Parses the RSS feed of a blomaga.
+    ///
+    /// # Examples
+    /// ```
+    /// # use niconico::channel::blomaga::BlomagaArticle;
+    /// let rss = r#"<?xml version="1.0" encoding="utf-8"?>
+    /// <rss version="2.0">
+    ///   <channel>
+    ///     <title>ブロマガ</title>
+    ///     <item>
+    ///       <title>お知らせ</title>
+    ///       <link>https://ch.nicovideo.jp/nicovideo-ch/blomaga/ar123456</link>
+    ///       <description>&lt;p&gt;本文&lt;/p&gt;</description>
+    ///       <pubDate>Tue, 01 Sep 2020 12:00:00 +0900</pubDate>
+    ///     </item>
+    ///   </channel>
+    /// </rss>"#;
+    /// let articles = BlomagaArticle::from_rss(rss)?;
+    /// assert_eq!(articles.len(), 1);
+    /// assert_eq!(articles[0].id, 123456);
+    /// assert_eq!(articles[0].description, "<p>本文</p>");
+    /// assert_eq!(articles[0].published_at.to_rfc3339(), "2020-09-01T12:00:00+09:00");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn from_rss(xml: &str) -> Result<Vec<BlomagaArticle>> {
+        let doc = roxmltree::Document::parse(xml).context(Error::InvalidResponse)?;
+        let channel = find_child(doc.root_element(), "channel")
+            .context("`channel` is missing")
+            .context(Error::InvalidResponse)?;
+
+        let mut articles = Vec::new();
+        for item in channel.children().filter(|n| n.has_tag_name("item")) {
+            let url = child_text(item, "link")?;
+            let id = url
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .unwrap_or_default()
+                .trim_start_matches("ar")
+                .parse()
+                .with_context(|| format!("`{}` is not an article url", url))
+                .context(Error::InvalidResponse)?;
+
+            articles.push(BlomagaArticle {
+                id,
+                title: child_text(item, "title")?.to_owned(),
+                url: url.to_owned(),
+                description: child_text(item, "description")
+                    .unwrap_or_default()
+                    .to_owned(),
+                published_at: DateTime::<FixedOffset>::parse_from_rfc2822(child_text(
+                    item, "pubDate",
+                )?)
+                .context(Error::InvalidResponse)?,
+            });
+        }
+        Ok(articles)
+    }
+}
+
+type FetchBlomagaArticleFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<VecDeque<BlomagaArticle>>> + 'a>>;
+
+/// Streams articles of the blomaga of a channel. See also [`ChannelId::stream_blomaga_articles`].
+pub struct BlomagaArticleStream<'a> {
+    session: &'a Session,
+    channel: ChannelId,
+    future: Option<FetchBlomagaArticleFuture<'a>>,
+    buf: VecDeque<BlomagaArticle>,
+    is_finished: bool,
+    next_page: u32,
+    /// The IDs of the articles fetched so far.
+    seen: HashSet<u64>,
+}
+impl<'a> Stream for BlomagaArticleStream<'a> {
+    type Item = Result<BlomagaArticle>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        if self.buf.is_empty() && self.future.is_none() && !self.is_finished {
+            self.future = Some(Box::pin(Self::gen_future(
+                self.session,
+                self.channel.clone(),
+                self.next_page,
+            )));
+        }
+        if let Some(future) = self.future.as_mut() {
+            match future.as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => {
+                    self.future = None;
+                    match result {
+                        Ok(mut buf) => {
+                            // the feed repeats the last page if the page is out of range
+                            let seen = &mut self.seen;
+                            buf.retain(|article| seen.insert(article.id));
+                            self.is_finished = buf.is_empty();
+                            self.buf = buf;
+                            self.next_page += 1;
+                        }
+                        Err(err) => return Poll::Ready(Some(Err(err))),
+                    }
+                }
+            }
+        }
+
+        Poll::Ready(Ok(self.buf.pop_front()).transpose())
+    }
+}
+impl<'a> BlomagaArticleStream<'a> {
+    pub fn new(session: &Session, channel: ChannelId) -> BlomagaArticleStream<'_> {
+        BlomagaArticleStream {
+            session,
+            channel,
+            future: None,
+            buf: VecDeque::new(),
+            is_finished: false,
+            next_page: 1,
+            seen: HashSet::new(),
+        }
+    }
+    async fn gen_future(
+        session: &'a Session,
+        channel: ChannelId,
+        page: u32,
+    ) -> Result<VecDeque<BlomagaArticle>> {
+        let url = format!(
+            "{}/{}/blomaga/nico/feed?page={}",
+            session.endpoints().ch,
+            channel,
+            page
+        );
+        let xml = session
+            .get_text(
+                &url,
+                RequestOptions {
+                    cookie_user_session: false,
+                    ..Default::default()
+                },
+            )
+            .await
+            .context("cannot fetch blomaga articles")?;
+
+        let mut articles = BlomagaArticle::from_rss(&xml)?;
+        articles.sort_by_key(|article| std::cmp::Reverse(article.id));
+        Ok(articles.into())
+    }
+}
@@ -0,0 +1,151 @@
+use super::ChannelId;
+use crate::*;
+use std::collections::VecDeque;
+use user::uploaded_video::UploadedVideoSort;
+use video::summary::VideoSummary;
+
+const PAGE_SIZE: usize = 100;
+
+type FetchChannelVideoFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<(u64, VecDeque<VideoSummary>, bool, usize)>> + 'a>>;
+
+/// Streams videos of a channel. See also [`ChannelId::stream_videos`].
+pub struct ChannelVideoStream<'a> {
+    session: &'a Session,
+    channel: ChannelId,
+    sort: UploadedVideoSort,
+    future: Option<FetchChannelVideoFuture<'a>>,
+    buf: VecDeque<VideoSummary>,
+    is_finished: bool,
+    next_page: u32,
+    len: Option<usize>,
+}
+impl<'a> Stream for ChannelVideoStream<'a> {
+    type Item = Result<VideoSummary>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        if self.buf.is_empty() && self.future.is_none() && !self.is_finished {
+            self.future = Some(Box::pin(Self::gen_future(
+                self.session,
+                self.channel.clone(),
+                self.sort,
+                self.next_page,
+            )));
+        }
+        if let Some(future) = self.future.as_mut() {
+            match future.as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => {
+                    self.future = None;
+                    match result {
+                        Ok((id, buf, is_finished, len)) => {
+                            self.channel = ChannelId::Number(id);
+                            self.buf = buf;
+                            self.is_finished = is_finished;
+                            self.next_page += 1;
+                            self.len = Some(len);
+                        }
+                        Err(err) => return Poll::Ready(Some(Err(err))),
+                    }
+                }
+            }
+        }
+
+        Poll::Ready(Ok(self.buf.pop_front()).transpose())
+    }
+}
+impl<'a> ChannelVideoStream<'a> {
+    pub fn new(
+        session: &Session,
+        channel: ChannelId,
+        sort: UploadedVideoSort,
+    ) -> ChannelVideoStream<'_> {
+        ChannelVideoStream {
+            session,
+            channel,
+            sort,
+            future: None,
+            buf: VecDeque::new(),
+            is_finished: false,
+            next_page: 1,
+            len: None,
+        }
+    }
+    /// Returns `(channel_id_number, videos, is_finished, len)`
+    async fn gen_future(
+        session: &'a Session,
+        channel: ChannelId,
+        sort: UploadedVideoSort,
+        page: u32,
+    ) -> Result<(u64, VecDeque<VideoSummary>, bool, usize)> {
+        // nvapi accepts only the ID number
+        let id = match channel {
+            ChannelId::Number(id) => id,
+            channel => channel.fetch_details(session).await?.id,
+        };
+
+        let url = gen_url(&session.endpoints().nvapi, id, sort, page);
+        let json = session
+            .get_json(
+                &url,
+                RequestOptions {
+                    header_x_frontend_id: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .context("cannot fetch channel videos")?;
+
+        let status = json_extract!(json, as_u64, ["meta"]["status"]);
+        match status {
+            200 => {}
+            404 => bail!(Error::ChannelNotFound),
+            _ => {
+                let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+                bail!(anyhow!("{} {}", status, error_code).context(Error::InvalidResponse))
+            }
+        }
+
+        let mut items = VecDeque::new();
+        for item in json_extract!(json, as_array, ["data"]["items"]) {
+            items.push_back(VideoSummary::from_json(&item["essential"])?);
+        }
+
+        let len = json_extract!(json, as_u64, ["data"]["totalCount"]) as usize;
+        let is_finished = items.len() < PAGE_SIZE || page as usize * PAGE_SIZE >= len;
+
+        Ok((id, items, is_finished, len))
+    }
+    /// Fetches the number of the videos.
+    pub async fn len(&mut self) -> Result<usize> {
+        // length is written in all response.
+        // if not received any response yet, do the first request.
+        if self.len.is_none() {
+            let (id, buf, is_finished, len) = Self::gen_future(
+                self.session,
+                self.channel.clone(),
+                self.sort,
+                self.next_page,
+            )
+            .await?;
+            self.channel = ChannelId::Number(id);
+            self.buf = buf;
+            self.is_finished = is_finished;
+            self.next_page += 1;
+            self.len = Some(len);
+        }
+
+        Ok(self.len.unwrap())
+    }
+}
+
+fn gen_url(nvapi: &str, id: u64, sort: UploadedVideoSort, page: u32) -> String {
+    format!(
+        "{}/v2/channels/{}/videos?pageSize={}&page={}&sortKey={}&sortOrder={}",
+        nvapi,
+        id,
+        PAGE_SIZE,
+        page,
+        sort.key.as_str(),
+        sort.order.as_str()
+    )
+}
@@ -0,0 +1,69 @@
+use super::ChannelId;
+use crate::*;
+
+/// Represents details of a channel.
+#[derive(Debug, Clone)]
+pub struct ChannelDetails {
+    /// The ID number of this channel. (without `ch`)
+    pub id: u64,
+    /// The screen name of this channel. `None` if this channel has no screen name.
+    pub screen_name: Option<String>,
+    /// The name of this channel.
+    pub name: String,
+    /// The description of this channel, decorated with HTML.
+    pub description: String,
+    /// The URL of the icon of this channel.
+    pub icon_url: String,
+    /// The name of the company which runs this channel. (提供会社)
+    pub company: String,
+    /// Whether this channel requires a paid membership to watch some of its contents.
+    pub is_paid: bool,
+}
+impl ChannelDetails {
+    /// Fetches the details of the channel. See also [`ChannelId::fetch_details`].
+    pub async fn fetch(session: &Session, id: &ChannelId) -> Result<ChannelDetails> {
+        let url = format!(
+            "{}/v1/channel/channelapp/channels/{}.json",
+            session.endpoints().public_api,
+            id
+        );
+        let json = session
+            .get_json(
+                &url,
+                RequestOptions {
+                    cookie_user_session: false,
+                    ..Default::default()
+                },
+            )
+            .await
+            .context("cannot fetch the channel details")?;
+
+        let status = json_extract!(json, as_u64, ["meta"]["status"]);
+        match status {
+            200 => {}
+            404 => bail!(Error::ChannelNotFound),
+            _ => {
+                let error_code = json_extract_optional!(json, as_str, ["meta"]["errorCode"]);
+                bail!(anyhow!("{} {}", status, error_code.unwrap_or_default())
+                    .context(Error::InvalidResponse))
+            }
+        }
+
+        ChannelDetails::from_json(&json["data"])
+    }
+    pub(crate) fn from_json(data: &serde_json::Value) -> Result<ChannelDetails> {
+        Ok(ChannelDetails {
+            id: json_extract!(data, as_u64, ["id"]),
+            screen_name: json_extract_optional!(data, as_str, ["screenName"])
+                .filter(|screen_name| !screen_name.is_empty())
+                .map(|screen_name| screen_name.to_owned()),
+            name: json_extract!(data, as_string, ["name"]),
+            description: json_extract_optional!(data, as_string, ["description"])
+                .unwrap_or_default(),
+            icon_url: json_extract!(data, as_string, ["thumbnailUrl"]),
+            company: json_extract_optional!(data, as_string, ["companyViewname"])
+                .unwrap_or_default(),
+            is_paid: !json_extract!(data, as_bool, ["isFree"]),
+        })
+    }
+}
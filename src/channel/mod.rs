@@ -0,0 +1,168 @@
+//! Niconico Channel (ニコニコチャンネル).
+
+use crate::*;
+use nicorepo::item::{NicorepoSender, NicorepoSenderType};
+use user::uploaded_video::UploadedVideoSort;
+
+pub mod blomaga;
+pub mod channel_video;
+pub mod details;
+
+/// Represents the ID of a channel, either the ID number or the screen name.
+///
+/// # Examples
+/// ```
+/// # use niconico::channel::ChannelId;
+/// let id: ChannelId = "ch2632720".parse()?;
+/// assert_eq!(id, ChannelId::Number(2632720));
+///
+/// let id: ChannelId = "https://ch.nicovideo.jp/ch2632720/video?ref=top".parse()?;
+/// assert_eq!(id, ChannelId::Number(2632720));
+///
+/// let id: ChannelId = "https://ch.nicovideo.jp/nicovideo-ch".parse()?;
+/// assert_eq!(id, ChannelId::ScreenName("nicovideo-ch".to_owned()));
+/// assert_eq!(id.channel_url(), "https://ch.nicovideo.jp/nicovideo-ch");
+///
+/// assert!("https://www.nicovideo.jp/user/1".parse::<ChannelId>().is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum ChannelId {
+    /// The ID number of the channel. (without `ch`)
+    Number(u64),
+    /// The screen name of the channel, such as `nicovideo-ch`.
+    ScreenName(String),
+}
+impl ChannelId {
+    /// Gets the url of the channel page.
+    pub fn channel_url(&self) -> String {
+        format!("https://ch.nicovideo.jp/{}", self)
+    }
+    /// Fetches the details of this channel.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use niconico::{*, channel::ChannelId};
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let session = Session::new(USER_AGENT, Language::Japanese);
+    /// let details = ChannelId::Number(2632720).fetch_details(&session).await?;
+    /// println!("{} by {}", details.name, details.company);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_details(&self, session: &Session) -> Result<details::ChannelDetails> {
+        details::ChannelDetails::fetch(session, self).await
+    }
+    /// Fetches the videos of this channel.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use niconico::{*, channel::ChannelId, user::uploaded_video::*};
+    /// use futures::StreamExt;
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let session = Session::new(USER_AGENT, Language::Japanese);
+    /// let sort = UploadedVideoSort {
+    ///     key: UploadedVideoSortKey::ViewCount,
+    ///     ..Default::default()
+    /// };
+    /// let mut videos = ChannelId::Number(2632720).stream_videos(&session, sort);
+    /// println!("{} videos", videos.len().await?);
+    /// while let Some(video) = videos.next().await {
+    ///     println!("{}", video?.title);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stream_videos<'a>(
+        &self,
+        session: &'a Session,
+        sort: UploadedVideoSort,
+    ) -> channel_video::ChannelVideoStream<'a> {
+        channel_video::ChannelVideoStream::new(session, self.clone(), sort)
+    }
+    /// Fetches the articles of the blomaga (ブロマガ) of this channel, from the newest.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use niconico::{*, channel::ChannelId};
+    /// use futures::StreamExt;
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let session = Session::new(USER_AGENT, Language::Japanese);
+    /// let id: ChannelId = "nicovideo-ch".parse()?;
+    /// let mut articles = id.stream_blomaga_articles(&session);
+    /// while let Some(article) = articles.next().await {
+    ///     let article = article?;
+    ///     println!("{} ({})", article.title, article.published_at);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stream_blomaga_articles<'a>(
+        &self,
+        session: &'a Session,
+    ) -> blomaga::BlomagaArticleStream<'a> {
+        blomaga::BlomagaArticleStream::new(session, self.clone())
+    }
+}
+impl std::str::FromStr for ChannelId {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // accepts channel urls such as `https://ch.nicovideo.jp/ch123/video`
+        let path = s.split(['?', '#'].as_ref()).next().unwrap_or_default();
+        let id = match path.find("://") {
+            Some(index) => {
+                let mut segments = path[index + 3..].split('/');
+                ensure!(
+                    segments.next() == Some("ch.nicovideo.jp"),
+                    anyhow!("`{}` is not a channel url", s).context(Error::InvalidId)
+                );
+                segments.next().unwrap_or_default()
+            }
+            None => path.trim_end_matches('/'),
+        };
+
+        if let Some(number) = id.strip_prefix("ch") {
+            if !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()) {
+                return Ok(ChannelId::Number(number.parse().context(Error::InvalidId)?));
+            }
+        }
+        let is_valid = !id.is_empty()
+            && id
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+        ensure!(
+            is_valid,
+            anyhow!("`{}` is not a channel id", s).context(Error::InvalidId)
+        );
+
+        Ok(ChannelId::ScreenName(id.to_owned()))
+    }
+}
+impl std::fmt::Display for ChannelId {
+    /// Formats as the path of the channel page, such as `ch123` or `nicovideo-ch`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChannelId::Number(id) => write!(f, "ch{}", id),
+            ChannelId::ScreenName(screen_name) => f.write_str(screen_name),
+        }
+    }
+}
+impl std::convert::TryFrom<&NicorepoSender> for ChannelId {
+    type Error = anyhow::Error;
+    /// Converts the sender of a nicorepo item.
+    /// Fails with `Error::InvalidId` if the sender is not a channel.
+    fn try_from(sender: &NicorepoSender) -> Result<Self, Self::Error> {
+        ensure!(
+            sender.sender_type == NicorepoSenderType::Channel,
+            anyhow!("the sender is not a channel: {:?}", sender.sender_type)
+                .context(Error::InvalidId)
+        );
+        Ok(ChannelId::Number(sender.id))
+    }
+}
@@ -9,7 +9,58 @@ pub enum Error {
     /// The desired data cannot be extracted from the response . This error should not occur.
     #[error("received invalid response")]
     InvalidResponse,
+    /// The given ID or URL is not in a valid format.
+    #[error("invalid id")]
+    InvalidId,
     /// Login is required.
     #[error("login is required")]
     LoginRequired,
+    /// The requested list is not public.
+    #[error("the list is private")]
+    PrivateList,
+    /// The video has been deleted.
+    #[error("the video has been deleted")]
+    VideoDeleted,
+    /// The video does not exist.
+    #[error("the video is not found")]
+    VideoNotFound,
+    /// The channel does not exist.
+    #[error("the channel is not found")]
+    ChannelNotFound,
+    /// Comments cannot be posted to the thread, because it is locked or read-only.
+    #[error("posting comments is restricted")]
+    CommentPostingRestricted,
+    /// The comment was rejected, for example by the NG filter or because it is too long.
+    #[error("the comment was rejected")]
+    CommentRejected,
+    /// The login user is already following the user.
+    #[error("already following the user")]
+    AlreadyFollowing,
+    /// The login user is following as many users as possible.
+    #[error("cannot follow any more users")]
+    FollowLimitReached,
+    /// The login user tried to follow or unfollow themselves.
+    #[error("cannot follow or unfollow oneself")]
+    SelfFollow,
+    /// The login user has as many mylists as possible.
+    /// Non-premium users can have 3 mylists and premium users can have 25.
+    #[error("cannot create any more mylists")]
+    MylistLimitReached {
+        /// Whether the limit is the one for premium users.
+        is_premium: bool,
+    },
+    /// The mylist has as many items as possible.
+    /// A mylist of a non-premium user can have 100 items and one of a premium user can have 500.
+    #[error("cannot add any more items to the mylist")]
+    MylistItemLimitReached {
+        /// Whether the limit is the one for premium users.
+        is_premium: bool,
+    },
+    /// The timeshift of the program can no longer be reserved or watched.
+    #[error("the timeshift has expired")]
+    TimeshiftExpired,
+    /// The login user has as many timeshift reservations as possible.
+    /// Non-premium users can have 10 reservations and premium users can have 2000.
+    #[error("cannot reserve any more timeshifts")]
+    TimeshiftLimitReached,
 }
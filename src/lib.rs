@@ -1,14 +1,23 @@
 #[macro_use]
 mod tools;
 
+pub mod channel;
 pub mod error;
+pub mod live;
+pub mod mylist;
 pub mod nicorepo;
+pub mod ranking;
+pub mod search;
+pub mod series;
 pub mod session;
 pub mod user;
+pub mod video;
+pub mod watch_later;
 
 pub use error::Error;
-pub use session::{Language, Session};
+pub use session::{Endpoints, Language, Session};
 pub use user::User;
+pub use video::VideoId;
 
 use anyhow::{anyhow, bail, ensure, Context, Result};
 use chrono::{DateTime, FixedOffset};
@@ -20,3 +29,19 @@ use std::{
     pin::Pin,
     task::{Context as TaskContext, Poll},
 };
+
+/// Represents an order of sorting, shared by the lists and the search.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+impl SortOrder {
+    /// Gets the value of query parameters such as `sortOrder`, which is `asc` or `desc`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SortOrder::Ascending => "asc",
+            SortOrder::Descending => "desc",
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use super::watch::{Room, WebSocket};
+use crate::*;
+use futures::{SinkExt, StreamExt};
+use std::{collections::VecDeque, time::Duration};
+use tokio::time::Interval;
+use tokio_tungstenite::tungstenite::{http, Message};
+use video::comments::Comment;
+
+/// The interval of messages sent to keep the connection.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Connects to the comment server of the room, and streams the comments posted to it.
+///
+/// The stream begins with at most 150 recent comments, followed by the comments posted live.
+///
+/// # Examples
+/// ```
+/// use futures::{SinkExt, StreamExt};
+/// use niconico::live::{chat, watch::Room};
+/// use tokio_tungstenite::tungstenite::Message;
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// // a stand-in for the comment server
+/// let mut listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+/// let room = Room {
+///     name: "アリーナ".to_owned(),
+///     message_server_uri: format!("ws://{}", listener.local_addr()?),
+///     thread_id: 12345,
+///     post_key: None,
+///     wayback_key: None,
+/// };
+/// let server = async move {
+///     let (socket, _) = listener.accept().await.unwrap();
+///     let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+///     let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+///     assert!(request.contains(r#""thread":"12345""#));
+///
+///     for message in &[
+///         r#"{"thread":{"resultcode":0,"thread":"12345","last_res":2}}"#,
+///         r#"{"chat":{"thread":"12345","no":1,"vpos":100,"date":1600000000,"mail":"184","content":"こんにちは"}}"#,
+///         r#"{"ping":{"content":"rf:0"}}"#,
+///         r#"{"chat":{"thread":"12345","no":2,"vpos":200,"date":1600000001,"premium":1,"content":"888"}}"#,
+///     ] {
+///         ws.send(Message::text(*message)).await.unwrap();
+///     }
+///     ws.close(None).await.unwrap();
+/// };
+///
+/// let client = async {
+///     let comments = chat::connect(&room).await?;
+///     comments.collect::<Vec<_>>().await.into_iter().collect::<anyhow::Result<Vec<_>>>()
+/// };
+/// let (_, comments) = futures::join!(server, client);
+/// let comments = comments?;
+///
+/// assert_eq!(comments.len(), 2);
+/// assert_eq!(comments[0].body, "こんにちは");
+/// assert!(comments[1].is_premium);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn connect(room: &Room) -> Result<ChatStream> {
+    let mut ws = open(room).await?;
+    send_thread_request(&mut ws, room, -150, None, 0).await?;
+
+    let state = ChatState {
+        ws,
+        keep_alive: tokio::time::interval_at(
+            tokio::time::Instant::now() + KEEP_ALIVE_INTERVAL,
+            KEEP_ALIVE_INTERVAL,
+        ),
+        is_finished: false,
+    };
+    let inner = futures::stream::unfold(state, |mut state| async move {
+        if state.is_finished {
+            return None;
+        }
+        match state.next_comment().await {
+            Ok(Some(comment)) => Some((Ok(comment), state)),
+            Ok(None) => None,
+            Err(err) => {
+                state.is_finished = true;
+                Some((Err(err), state))
+            }
+        }
+    });
+    Ok(ChatStream {
+        inner: Box::pin(inner),
+    })
+}
+
+/// Connects to the comment server of the room, and streams the comments posted before `until`,
+/// from the newest to the oldest. This is mainly useful to fetch the comments of a timeshift,
+/// passing [`ProgramDetails::end_at`](super::details::ProgramDetails::end_at) as `until`.
+///
+/// The comments are fetched by paging backward, 1000 comments at a time.
+/// Fetching past comments of a timeshift requires [`Room::wayback_key`].
+///
+/// # Examples
+/// ```
+/// use chrono::TimeZone;
+/// use futures::{SinkExt, StreamExt};
+/// use niconico::live::{chat, watch::Room};
+/// use tokio_tungstenite::tungstenite::Message;
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// // a stand-in for the comment server, which has comments No. 1 to 4
+/// let mut listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+/// let room = Room {
+///     name: "アリーナ".to_owned(),
+///     message_server_uri: format!("ws://{}", listener.local_addr()?),
+///     thread_id: 12345,
+///     post_key: None,
+///     wayback_key: Some("key".to_owned()),
+/// };
+/// let server = async move {
+///     let (socket, _) = listener.accept().await.unwrap();
+///     let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+///     // the second page has only a comment fetched already, so the next page is fetched a second earlier
+///     let pages = [(1600000010, &[3, 4][..]), (1600000004, &[3][..]), (1600000003, &[1, 2][..])];
+///     for (page, (when, chats)) in pages.iter().enumerate() {
+///         let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+///         assert!(request.contains(r#""waybackkey":"key""#));
+///         assert!(request.contains(&format!(r#""when":{}"#, when)));
+///
+///         for no in *chats {
+///             let chat = format!(
+///                 r#"{{"chat":{{"thread":"12345","no":{0},"vpos":{0}00,"date":160000000{0},"content":"{0}"}}}}"#,
+///                 no
+///             );
+///             ws.send(Message::text(chat)).await.unwrap();
+///         }
+///         let ping = format!(r#"{{"ping":{{"content":"rf:{}"}}}}"#, page);
+///         ws.send(Message::text(ping)).await.unwrap();
+///     }
+/// };
+///
+/// let client = async {
+///     let until = chrono::FixedOffset::east(9 * 3600).timestamp(1600000010, 0);
+///     let comments = chat::stream_past(&room, until).await?;
+///     comments.collect::<Vec<_>>().await.into_iter().collect::<anyhow::Result<Vec<_>>>()
+/// };
+/// let (_, comments) = futures::join!(server, client);
+/// let numbers: Vec<_> = comments?.iter().map(|comment| comment.no).collect();
+/// assert_eq!(numbers, [4, 3, 2, 1]);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn stream_past(room: &Room, until: DateTime<FixedOffset>) -> Result<ChatStream> {
+    let ws = open(room).await?;
+
+    let state = PastChatState {
+        ws,
+        room: room.clone(),
+        buf: VecDeque::new(),
+        when: until.timestamp(),
+        oldest_no: None,
+        page: 0,
+        is_finished: false,
+    };
+    let inner = futures::stream::unfold(state, |mut state| async move {
+        while state.buf.is_empty() && !state.is_finished {
+            if let Err(err) = state.fetch_page().await {
+                state.is_finished = true;
+                return Some((Err(err), state));
+            }
+        }
+        let comment = state.buf.pop_front()?;
+        Some((Ok(comment), state))
+    });
+    Ok(ChatStream {
+        inner: Box::pin(inner),
+    })
+}
+
+/// Streams comments from the comment server. See also [`connect`].
+pub struct ChatStream {
+    inner: Pin<Box<dyn Stream<Item = Result<Comment>> + Send>>,
+}
+impl Stream for ChatStream {
+    type Item = Result<Comment>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+struct ChatState {
+    ws: WebSocket,
+    keep_alive: Interval,
+    is_finished: bool,
+}
+impl ChatState {
+    /// Returns `None` if the connection is closed.
+    async fn next_comment(&mut self) -> Result<Option<Comment>> {
+        loop {
+            let message = tokio::select! {
+                message = self.ws.next() => message,
+                _ = self.keep_alive.tick() => {
+                    self.ws
+                        .send(Message::Text(String::new()))
+                        .await
+                        .context("cannot send to the comment server")?;
+                    continue;
+                }
+            };
+            let text = match message {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(_))) | None => return Ok(None),
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => {
+                    bail!(anyhow!(err).context("cannot receive from the comment server"))
+                }
+            };
+
+            let json: serde_json::Value =
+                serde_json::from_str(&text).context(Error::InvalidResponse)?;
+            if let Some(chat) = json.get("chat") {
+                return Comment::from_json(chat).map(Some);
+            }
+            if let Some(thread) = json.get("thread") {
+                check_thread_result(thread)?;
+            }
+            // such as `ping`
+        }
+    }
+}
+
+struct PastChatState {
+    ws: WebSocket,
+    room: Room,
+    buf: VecDeque<Comment>,
+    /// The UNIX time before which the next page is fetched.
+    when: i64,
+    /// The number of the oldest comment fetched so far.
+    oldest_no: Option<u64>,
+    page: u64,
+    is_finished: bool,
+}
+impl PastChatState {
+    /// Fetches the comments before `when` into `buf`, from the newest to the oldest.
+    async fn fetch_page(&mut self) -> Result<()> {
+        send_thread_request(&mut self.ws, &self.room, -1000, Some(self.when), self.page).await?;
+
+        let end_of_page = format!("rf:{}", self.page);
+        let mut comments = Vec::new();
+        loop {
+            let text = match self.ws.next().await {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(_))) | None => {
+                    let err = anyhow!("closed before the end of the page");
+                    bail!(err.context("cannot receive from the comment server"))
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => {
+                    bail!(anyhow!(err).context("cannot receive from the comment server"))
+                }
+            };
+
+            let json: serde_json::Value =
+                serde_json::from_str(&text).context(Error::InvalidResponse)?;
+            if let Some(chat) = json.get("chat") {
+                comments.push(Comment::from_json(chat)?);
+            } else if let Some(thread) = json.get("thread") {
+                check_thread_result(thread)?;
+            } else if json_extract_optional!(json, as_str, ["ping"]["content"])
+                == Some(&end_of_page)
+            {
+                break;
+            }
+        }
+        self.page += 1;
+
+        // decide the end before filtering, since a page can consist of the comments fetched already
+        self.is_finished = match comments.iter().map(|comment| comment.no).min() {
+            Some(no) => no <= 1,
+            None => true,
+        };
+
+        // the comments posted in the same second as `when` can be fetched twice
+        if let Some(oldest_no) = self.oldest_no {
+            comments.retain(|comment| comment.no < oldest_no);
+        }
+        comments.sort_by_key(|comment| std::cmp::Reverse(comment.no));
+
+        match comments.last() {
+            Some(oldest) => {
+                self.oldest_no = Some(oldest.no);
+                self.when = oldest.posted_at.timestamp() + 1;
+            }
+            // all of the page is fetched already, so go back a second not to fetch it again
+            None => self.when -= 1,
+        }
+        self.buf.extend(comments);
+        Ok(())
+    }
+}
+
+/// Opens the WebSocket of the comment server of the room.
+async fn open(room: &Room) -> Result<WebSocket> {
+    let request = http::Request::builder()
+        .uri(&room.message_server_uri)
+        .header("Sec-WebSocket-Protocol", "msg.nicovideo.jp#json")
+        .body(())
+        .context("invalid message server uri")
+        .context(Error::InvalidResponse)?;
+    let (ws, _) = tokio_tungstenite::connect_async(request)
+        .await
+        .context("cannot connect to the comment server")?;
+    Ok(ws)
+}
+
+/// Requests the comments of the room, surrounded by `ping`s numbered `seq`.
+async fn send_thread_request(
+    ws: &mut WebSocket,
+    room: &Room,
+    res_from: i64,
+    when: Option<i64>,
+    seq: u64,
+) -> Result<()> {
+    let mut thread = serde_json::json!({
+        "thread": room.thread_id.to_string(),
+        "version": "20061206",
+        "user_id": "guest",
+        "res_from": res_from,
+        "with_global": 1,
+        "scores": 1,
+        "nicoru": 0,
+    });
+    if let Some(when) = when {
+        thread["when"] = when.into();
+        if let Some(wayback_key) = &room.wayback_key {
+            thread["waybackkey"] = wayback_key.as_str().into();
+        }
+    }
+
+    let request = serde_json::json!([
+        { "ping": { "content": format!("rs:{}", seq) } },
+        { "ping": { "content": format!("ps:{}", seq) } },
+        { "thread": thread },
+        { "ping": { "content": format!("pf:{}", seq) } },
+        { "ping": { "content": format!("rf:{}", seq) } },
+    ]);
+    ws.send(Message::Text(request.to_string()))
+        .await
+        .context("cannot send to the comment server")
+}
+
+fn check_thread_result(thread: &serde_json::Value) -> Result<()> {
+    let result_code = json_extract!(thread, as_u64, ["resultcode"]);
+    ensure!(
+        result_code == 0,
+        anyhow!("the thread request failed: {}", result_code).context(Error::InvalidResponse)
+    );
+    Ok(())
+}
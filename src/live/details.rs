@@ -0,0 +1,142 @@
+use super::{timestamp, ProgramId};
+use crate::*;
+
+/// Represents details of a live program.
+#[derive(Debug, Clone)]
+pub struct ProgramDetails {
+    /// The ID of this program.
+    pub id: ProgramId,
+    /// The title of this program.
+    pub title: String,
+    /// The description of this program, decorated with HTML.
+    pub description: String,
+    /// The status of this program.
+    pub status: ProgramStatus,
+    /// The date when the doors open, which is before the program begins.
+    pub open_at: DateTime<FixedOffset>,
+    /// The date when this program begins.
+    pub begin_at: DateTime<FixedOffset>,
+    /// The date when this program ends, or is scheduled to end.
+    pub end_at: DateTime<FixedOffset>,
+    /// The broadcaster of this program.
+    pub provider: ProgramProvider,
+    /// The tags of this program.
+    pub tags: Vec<ProgramTag>,
+    /// Whether this program can be watched later as a timeshift.
+    pub is_timeshift_enabled: bool,
+    /// The URL of the WebSocket to watch this program. `None` if this program cannot be watched now.
+    pub web_socket_url: Option<String>,
+}
+impl ProgramDetails {
+    /// Fetches the details of the program. See also [`ProgramId::fetch_details`](super::ProgramId::fetch_details).
+    pub async fn fetch(session: &Session, id: &ProgramId) -> Result<ProgramDetails> {
+        let data = super::fetch_embedded_data(session, id).await?;
+        ProgramDetails::from_json(&data)
+    }
+    pub(crate) fn from_json(data: &serde_json::Value) -> Result<ProgramDetails> {
+        let program = &data["program"];
+
+        let status = match json_extract!(program, as_str, ["status"]) {
+            "RELEASED" | "BEFORE_RELEASE" => ProgramStatus::Reserved,
+            "ON_AIR" => ProgramStatus::OnAir,
+            "ENDED" => ProgramStatus::Ended,
+            status => bail!(
+                anyhow!("unknown program status: `{}`", status).context(Error::InvalidResponse)
+            ),
+        };
+
+        let supplier = &program["supplier"];
+        let provider = match json_extract!(program, as_str, ["providerType"]) {
+            "community" => ProgramProvider::User {
+                user: User::UserId(
+                    json_extract!(supplier, as_str, ["programProviderId"])
+                        .parse()
+                        .context(Error::InvalidResponse)?,
+                ),
+                name: json_extract!(supplier, as_string, ["name"]),
+            },
+            "channel" => ProgramProvider::Channel {
+                id: json_extract!(supplier, as_str, ["programProviderId"])
+                    .trim_start_matches("ch")
+                    .parse()
+                    .context(Error::InvalidResponse)?,
+                name: json_extract!(supplier, as_string, ["name"]),
+            },
+            "official" => ProgramProvider::Official {
+                name: json_extract!(supplier, as_string, ["name"]),
+            },
+            provider_type => bail!(anyhow!("unknown provider type: `{}`", provider_type)
+                .context(Error::InvalidResponse)),
+        };
+
+        let mut tags = Vec::new();
+        for tag in json_extract!(program, as_array, ["tag"]["list"]) {
+            tags.push(ProgramTag {
+                name: json_extract!(tag, as_string, ["text"]),
+                is_locked: json_extract_optional!(tag, as_bool, ["isLocked"]).unwrap_or_default(),
+            });
+        }
+
+        Ok(ProgramDetails {
+            id: json_extract!(program, as_str, ["nicoliveProgramId"]).parse()?,
+            title: json_extract!(program, as_string, ["title"]),
+            description: json_extract!(program, as_string, ["description"]),
+            status,
+            open_at: timestamp(json_extract!(program, as_i64, ["openTime"]))?,
+            begin_at: timestamp(json_extract!(program, as_i64, ["beginTime"]))?,
+            end_at: timestamp(json_extract!(program, as_i64, ["endTime"]))?,
+            provider,
+            tags,
+            is_timeshift_enabled: !data["programTimeshift"]["publication"].is_null(),
+            web_socket_url: json_extract_optional!(
+                data,
+                as_str,
+                ["site"]["relive"]["webSocketUrl"]
+            )
+            .filter(|url| !url.is_empty())
+            .map(|url| url.to_owned()),
+        })
+    }
+}
+
+/// Represents the status of a live program.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ProgramStatus {
+    /// The program has not begun yet.
+    Reserved,
+    /// The program is on air.
+    OnAir,
+    /// The program has ended.
+    Ended,
+}
+/// Represents the broadcaster of a live program.
+#[derive(Debug, Clone)]
+pub enum ProgramProvider {
+    /// A user. (ユーザー生放送)
+    User {
+        /// The `User` that represents the broadcaster.
+        user: User,
+        /// The nickname of the broadcaster.
+        name: String,
+    },
+    /// A channel. (チャンネル生放送)
+    Channel {
+        /// The ID number of the channel. (without `ch`)
+        id: u64,
+        /// The name of the channel.
+        name: String,
+    },
+    /// Niconico itself. (公式生放送)
+    Official {
+        /// The name of the broadcaster.
+        name: String,
+    },
+}
+/// Represents a tag of a live program.
+#[derive(Debug, Clone)]
+pub struct ProgramTag {
+    /// The name of the tag.
+    pub name: String,
+    /// Whether the tag is locked by the broadcaster.
+    pub is_locked: bool,
+}
@@ -0,0 +1,140 @@
+//! Niconico Live (ニコニコ生放送).
+
+use crate::*;
+use chrono::TimeZone;
+use video::comments::comment::jst;
+
+pub mod chat;
+pub mod details;
+pub mod timeshift;
+pub mod watch;
+
+/// Represents the ID of a live program, such as `lv1234567`.
+///
+/// # Examples
+/// ```
+/// # use niconico::live::ProgramId;
+/// let id: ProgramId = "lv1234567".parse()?;
+/// assert_eq!(id.as_str(), "lv1234567");
+///
+/// let id: ProgramId = "https://live.nicovideo.jp/watch/lv1234567?ref=top".parse()?;
+/// assert_eq!(id.as_str(), "lv1234567");
+///
+/// assert!("sm9".parse::<ProgramId>().is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ProgramId(String);
+impl ProgramId {
+    /// Returns the ID as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+    /// Gets the url of the watch page.
+    pub fn watch_url(&self) -> String {
+        format!("https://live.nicovideo.jp/watch/{}", self.0)
+    }
+    /// Fetches the details of this program.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use niconico::{*, live::ProgramId};
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let session = Session::new(USER_AGENT, Language::Japanese);
+    /// let details = "lv1234567".parse::<ProgramId>()?.fetch_details(&session).await?;
+    /// println!("{} ({:?}), begins at {}", details.title, details.status, details.begin_at);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_details(&self, session: &Session) -> Result<details::ProgramDetails> {
+        details::ProgramDetails::fetch(session, self).await
+    }
+    /// Connects to the watch server of this program, to receive the media stream and the comment rooms.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// # use niconico::{*, live::{chat, watch::WatchEvent, ProgramId}};
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let session = Session::new(USER_AGENT, Language::Japanese);
+    /// let mut events = "lv1234567".parse::<ProgramId>()?.watch(&session).await?;
+    /// while let Some(event) = events.next().await {
+    ///     match event? {
+    ///         WatchEvent::Stream(stream) => println!("playlist: {}", stream.uri),
+    ///         WatchEvent::Room(room) => {
+    ///             let mut comments = chat::connect(&room).await?;
+    ///             while let Some(comment) = comments.next().await {
+    ///                 println!("{}", comment?.body);
+    ///             }
+    ///         }
+    ///         WatchEvent::Statistics(statistics) => println!("{} viewers", statistics.viewers),
+    ///         WatchEvent::Disconnect(reason) => println!("disconnected: {:?}", reason),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn watch(&self, session: &Session) -> Result<watch::WatchStream> {
+        watch::watch(session, self).await
+    }
+}
+impl std::str::FromStr for ProgramId {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // accepts watch urls such as `https://live.nicovideo.jp/watch/lv123` or `https://nico.ms/lv123`
+        let id = s
+            .split(['?', '#'].as_ref())
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default();
+
+        let is_valid =
+            id.starts_with("lv") && id.len() > 2 && id[2..].bytes().all(|b| b.is_ascii_digit());
+        ensure!(
+            is_valid,
+            anyhow!("`{}` is not a live program id", s).context(Error::InvalidId)
+        );
+
+        Ok(ProgramId(id.to_owned()))
+    }
+}
+impl std::fmt::Display for ProgramId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Fetches the watch page and extracts the data embedded in it.
+pub(crate) async fn fetch_embedded_data(
+    session: &Session,
+    id: &ProgramId,
+) -> Result<serde_json::Value> {
+    html_extractor::html_extractor! {
+        WatchPage {
+            embedded_data: String = (attr["data-props"] of "#embedded-data")
+        }
+    }
+
+    let url = format!("{}/watch/{}", session.endpoints().live, id);
+    let watch_page: WatchPage = session
+        .get_data(&url, None)
+        .await
+        .context("cannot fetch the watch page")?;
+    let data = serde_json::from_str(&watch_page.embedded_data).context(Error::InvalidResponse)?;
+    Ok(data)
+}
+
+fn timestamp(secs: i64) -> Result<DateTime<FixedOffset>> {
+    jst()
+        .timestamp_opt(secs, 0)
+        .single()
+        .context("the time is invalid")
+        .context(Error::InvalidResponse)
+}
@@ -0,0 +1,169 @@
+//! Manages the timeshift reservations (タイムシフト予約) of the login user.
+//!
+//! A program can be watched after it has ended by reserving its timeshift.
+//! The reservation must be used before watching, and then can be watched until it expires.
+//! Past comments of the program can be fetched with [`chat::stream_past`](super::chat::stream_past).
+
+use super::{timestamp, ProgramId};
+use crate::*;
+
+/// Represents a timeshift reservation of the login user.
+#[derive(Debug, Clone)]
+pub struct TimeshiftReservation {
+    /// The ID of the reserved program.
+    pub program: ProgramId,
+    /// The title of the reserved program.
+    pub title: String,
+    /// The date when the reserved program begins.
+    pub begin_at: DateTime<FixedOffset>,
+    /// The status of this reservation.
+    pub status: TimeshiftStatus,
+    /// The date until which the timeshift can be watched. `None` if not decided yet.
+    pub expires_at: Option<DateTime<FixedOffset>>,
+}
+
+/// Represents the status of a timeshift reservation.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TimeshiftStatus {
+    /// The reservation has not been used yet.
+    Reserved,
+    /// The reservation has been used, and the timeshift can be watched until it expires.
+    InUse,
+    /// The timeshift can no longer be watched.
+    Expired,
+}
+
+/// Fetches the timeshift reservations of the login user.
+///
+/// # Errors
+/// `Error::LoginRequired` if the session is not logged in.
+///
+/// # Examples
+/// ```no_run
+/// # use niconico::{*, live::{timeshift::{self, TimeshiftStatus}, ProgramId}};
+/// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// # let mut session = Session::new(USER_AGENT, Language::Japanese);
+/// # session.set_cookie_user_session(&std::env::var("NICO_SID")?);
+/// let id: ProgramId = "lv1234567".parse()?;
+/// timeshift::reserve(&session, &id).await?;
+///
+/// for reservation in timeshift::fetch_reservations(&session).await? {
+///     if reservation.status == TimeshiftStatus::Reserved {
+///         println!("{} is not used yet", reservation.title);
+///     }
+/// }
+///
+/// timeshift::use_reservation(&session, &id).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn fetch_reservations(session: &Session) -> Result<Vec<TimeshiftReservation>> {
+    ensure!(session.is_logged_in(), Error::LoginRequired);
+    let url = format!("{}/api/v2/timeshift/reservations", session.endpoints().live);
+    let request = session.get(&url, request_options());
+    let json = send(session, request)
+        .await
+        .context("cannot fetch the timeshift reservations")?;
+
+    let mut reservations = Vec::new();
+    for item in json_extract!(json, as_array, ["data"]["items"]) {
+        let status =
+            match json_extract!(item, as_str, ["status"]) {
+                "RESERVED" => TimeshiftStatus::Reserved,
+                "USED" => TimeshiftStatus::InUse,
+                "EXPIRED" => TimeshiftStatus::Expired,
+                status => bail!(anyhow!("unknown reservation status: `{}`", status)
+                    .context(Error::InvalidResponse)),
+            };
+        let expires_at = match json_extract_optional!(item, as_i64, ["expireTime"]) {
+            Some(secs) => Some(timestamp(secs)?),
+            None => None,
+        };
+        reservations.push(TimeshiftReservation {
+            program: json_extract!(item, as_str, ["programId"]).parse()?,
+            title: json_extract!(item, as_string, ["title"]),
+            begin_at: timestamp(json_extract!(item, as_i64, ["beginTime"]))?,
+            status,
+            expires_at,
+        });
+    }
+    Ok(reservations)
+}
+
+/// Reserves the timeshift of the program. See [`fetch_reservations`] for examples.
+///
+/// # Errors
+/// - `Error::LoginRequired` if the session is not logged in.
+/// - `Error::TimeshiftExpired` if the timeshift can no longer be reserved.
+/// - `Error::TimeshiftLimitReached` if the login user has as many reservations as possible.
+pub async fn reserve(session: &Session, id: &ProgramId) -> Result<()> {
+    let request = session.post(&gen_url(session, id)?, request_options());
+    send(session, request)
+        .await
+        .context("cannot reserve the timeshift")?;
+    Ok(())
+}
+/// Cancels the timeshift reservation of the program.
+///
+/// # Errors
+/// `Error::LoginRequired` if the session is not logged in.
+pub async fn cancel(session: &Session, id: &ProgramId) -> Result<()> {
+    let request = session.delete(&gen_url(session, id)?, request_options());
+    send(session, request)
+        .await
+        .context("cannot cancel the timeshift reservation")?;
+    Ok(())
+}
+/// Uses the timeshift reservation of the program, which is required before watching the timeshift.
+/// The watching period begins when the reservation is used. See [`fetch_reservations`] for examples.
+///
+/// # Errors
+/// - `Error::LoginRequired` if the session is not logged in.
+/// - `Error::TimeshiftExpired` if the timeshift can no longer be watched.
+pub async fn use_reservation(session: &Session, id: &ProgramId) -> Result<()> {
+    let request = session
+        .patch(&gen_url(session, id)?, request_options())
+        .form(&[("action", "use")]);
+    send(session, request)
+        .await
+        .context("cannot use the timeshift reservation")?;
+    Ok(())
+}
+
+async fn send(session: &Session, request: reqwest::RequestBuilder) -> Result<serde_json::Value> {
+    let json = session.send_json(request).await?;
+
+    let status = json_extract!(json, as_u64, ["meta"]["status"]);
+    match status {
+        200 | 201 => Ok(json),
+        401 => bail!(Error::LoginRequired),
+        _ => {
+            let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+            match error_code {
+                // the period differs between non-premium and premium users
+                "EXPIRED_GENERAL" | "EXPIRED_PREMIUM" => bail!(Error::TimeshiftExpired),
+                "OVERLIMIT" => bail!(Error::TimeshiftLimitReached),
+                _ => bail!(anyhow!("{} {}", status, error_code).context(Error::InvalidResponse)),
+            }
+        }
+    }
+}
+
+fn request_options() -> RequestOptions {
+    RequestOptions {
+        header_x_frontend_id: true,
+        header_x_request_with: true,
+        ..Default::default()
+    }
+}
+
+fn gen_url(session: &Session, id: &ProgramId) -> Result<String> {
+    ensure!(session.is_logged_in(), Error::LoginRequired);
+    Ok(format!(
+        "{}/api/v2/programs/{}/timeshift/reservation",
+        session.endpoints().live,
+        id
+    ))
+}
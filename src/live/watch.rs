@@ -0,0 +1,332 @@
+use super::ProgramId;
+use crate::*;
+use futures::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::{net::TcpStream, time::Interval};
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+pub(crate) type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Represents an event sent from the watch server of a live program.
+#[derive(Debug, Clone)]
+pub enum WatchEvent {
+    /// The media stream is ready to be played.
+    Stream(StreamInfo),
+    /// The comment room to receive the comments. Pass it to [`chat::connect`](super::chat::connect).
+    Room(Room),
+    /// The statistics of the program are updated.
+    Statistics(Statistics),
+    /// The server has disconnected. This is the last event of the stream.
+    Disconnect(DisconnectReason),
+}
+
+/// Represents the media stream of a live program.
+#[derive(Debug, Clone)]
+pub struct StreamInfo {
+    /// The URL of the HLS playlist (`master.m3u8`).
+    pub uri: String,
+    /// The quality of the stream, such as `abr` or `super_high`.
+    pub quality: String,
+    /// The qualities available for this program.
+    pub available_qualities: Vec<String>,
+}
+
+/// Represents a comment room of a live program.
+#[derive(Debug, Clone)]
+pub struct Room {
+    /// The name of the room, such as `アリーナ`.
+    pub name: String,
+    /// The URL of the WebSocket of the comment server.
+    pub message_server_uri: String,
+    /// The ID number of the comment thread.
+    pub thread_id: u64,
+    /// The key to post comments to the room. `None` if the login user cannot post comments.
+    pub post_key: Option<String>,
+    /// The key to fetch past comments of the room. Available when watching a timeshift.
+    pub wayback_key: Option<String>,
+}
+
+/// Represents the statistics of a live program.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Statistics {
+    /// The number of the viewers. (来場者数)
+    pub viewers: u64,
+    /// The number of the comments.
+    pub comments: u64,
+    /// The points of the advertisements. (ニコニ広告) Not available for some programs.
+    pub ad_points: Option<u64>,
+    /// The points of the gifts. (ギフト) Not available for some programs.
+    pub gift_points: Option<u64>,
+}
+
+/// Represents the reason why the watch server disconnected.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DisconnectReason {
+    /// The program is being watched in another connection, such as another tab.
+    Takeover,
+    /// The login user is not allowed to watch the program.
+    NoPermission,
+    /// The program has ended.
+    EndProgram,
+    /// `pong` was not sent in time.
+    PingTimeout,
+    /// The login user has too many connections.
+    TooManyConnections,
+    /// The login user is watching too many programs.
+    TooManyWatchings,
+    /// The program is too crowded to watch.
+    Crowded,
+    /// The service is under maintenance.
+    MaintenanceIn,
+    /// The service is temporarily unavailable.
+    ServiceTemporarilyUnavailable,
+    /// A reason not listed above.
+    Other(String),
+}
+impl DisconnectReason {
+    fn parse(reason: &str) -> DisconnectReason {
+        match reason {
+            "TAKEOVER" => DisconnectReason::Takeover,
+            "NO_PERMISSION" => DisconnectReason::NoPermission,
+            "END_PROGRAM" => DisconnectReason::EndProgram,
+            "PING_TIMEOUT" => DisconnectReason::PingTimeout,
+            "TOO_MANY_CONNECTIONS" => DisconnectReason::TooManyConnections,
+            "TOO_MANY_WATCHINGS" => DisconnectReason::TooManyWatchings,
+            "CROWDED" => DisconnectReason::Crowded,
+            "MAINTENANCE_IN" => DisconnectReason::MaintenanceIn,
+            "SERVICE_TEMPORARILY_UNAVAILABLE" => DisconnectReason::ServiceTemporarilyUnavailable,
+            reason => DisconnectReason::Other(reason.to_owned()),
+        }
+    }
+}
+
+/// Connects to the watch server of the program. See also [`ProgramId::watch`].
+pub async fn watch(session: &Session, id: &ProgramId) -> Result<WatchStream> {
+    let details = id.fetch_details(session).await?;
+    let url = details
+        .web_socket_url
+        .with_context(|| format!("`{}` cannot be watched now", id))?;
+    connect(&url).await
+}
+
+/// Connects to the watch server with the URL of the WebSocket,
+/// which is [`ProgramDetails::web_socket_url`](super::details::ProgramDetails::web_socket_url).
+///
+/// The stream sends `startWatching` on connection, and answers `ping` and keeps the seat while it is polled.
+///
+/// # Examples
+/// ```
+/// use futures::{SinkExt, StreamExt};
+/// use niconico::live::watch::{self, WatchEvent};
+/// use tokio_tungstenite::tungstenite::Message;
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// // a stand-in for the watch server
+/// let mut listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+/// let url = format!("ws://{}", listener.local_addr()?);
+/// let server = async move {
+///     let (socket, _) = listener.accept().await.unwrap();
+///     let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+///     let start_watching = ws.next().await.unwrap().unwrap().into_text().unwrap();
+///     assert!(start_watching.contains(r#""type":"startWatching""#));
+///
+///     ws.send(Message::text(r#"{"type":"ping"}"#)).await.unwrap();
+///     let pong = ws.next().await.unwrap().unwrap().into_text().unwrap();
+///     assert_eq!(pong, r#"{"type":"pong"}"#);
+///
+///     for message in &[
+///         r#"{"type":"seat","data":{"keepIntervalSec":30}}"#,
+///         r#"{"type":"stream","data":{"uri":"https://example.com/master.m3u8","quality":"abr","availableQualities":["abr","high"]}}"#,
+///         r#"{"type":"room","data":{"name":"アリーナ","messageServer":{"uri":"wss://example.com/websocket","type":"niwavided"},"threadId":"12345","yourPostKey":"key"}}"#,
+///         r#"{"type":"statistics","data":{"viewers":10,"comments":3}}"#,
+///         r#"{"type":"disconnect","data":{"reason":"END_PROGRAM"}}"#,
+///     ] {
+///         ws.send(Message::text(*message)).await.unwrap();
+///     }
+/// };
+///
+/// let client = async {
+///     let events = watch::connect(&url).await?;
+///     events.collect::<Vec<_>>().await.into_iter().collect::<anyhow::Result<Vec<_>>>()
+/// };
+/// let (_, events) = futures::join!(server, client);
+/// let events = events?;
+///
+/// assert_eq!(events.len(), 4);
+/// assert!(matches!(&events[0], WatchEvent::Stream(stream) if stream.quality == "abr"));
+/// assert!(matches!(&events[1], WatchEvent::Room(room) if room.thread_id == 12345));
+/// assert!(matches!(&events[2], WatchEvent::Statistics(statistics) if statistics.viewers == 10));
+/// assert!(matches!(&events[3], WatchEvent::Disconnect(watch::DisconnectReason::EndProgram)));
+/// # Ok(())
+/// # }
+/// ```
+pub async fn connect(web_socket_url: &str) -> Result<WatchStream> {
+    let (mut ws, _) = tokio_tungstenite::connect_async(web_socket_url)
+        .await
+        .context("cannot connect to the watch server")?;
+
+    let start_watching = serde_json::json!({
+        "type": "startWatching",
+        "data": {
+            "stream": {
+                "quality": "abr",
+                "protocol": "hls",
+                "latency": "low",
+                "chasePlay": false,
+            },
+            "room": {
+                "protocol": "webSocket",
+                "commentable": true,
+            },
+            "reconnect": false,
+        },
+    });
+    ws.send(Message::Text(start_watching.to_string()))
+        .await
+        .context("cannot start watching")?;
+
+    let state = WatchState {
+        ws,
+        keep_seat: None,
+        is_finished: false,
+    };
+    let inner = futures::stream::unfold(state, |mut state| async move {
+        if state.is_finished {
+            return None;
+        }
+        match state.next_event().await {
+            Ok(Some(event)) => {
+                state.is_finished = matches!(event, WatchEvent::Disconnect(_));
+                Some((Ok(event), state))
+            }
+            Ok(None) => None,
+            Err(err) => {
+                state.is_finished = true;
+                Some((Err(err), state))
+            }
+        }
+    });
+    Ok(WatchStream {
+        inner: Box::pin(inner),
+    })
+}
+
+/// Streams events from the watch server. See also [`connect`].
+pub struct WatchStream {
+    inner: Pin<Box<dyn Stream<Item = Result<WatchEvent>> + Send>>,
+}
+impl Stream for WatchStream {
+    type Item = Result<WatchEvent>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+struct WatchState {
+    ws: WebSocket,
+    keep_seat: Option<Interval>,
+    is_finished: bool,
+}
+impl WatchState {
+    /// Returns `None` if the connection is closed.
+    async fn next_event(&mut self) -> Result<Option<WatchEvent>> {
+        loop {
+            let message = match &mut self.keep_seat {
+                Some(keep_seat) => tokio::select! {
+                    message = self.ws.next() => message,
+                    _ = keep_seat.tick() => {
+                        self.send(r#"{"type":"keepSeat"}"#).await?;
+                        continue;
+                    }
+                },
+                None => self.ws.next().await,
+            };
+            let text = match message {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(_))) | None => return Ok(None),
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => {
+                    bail!(anyhow!(err).context("cannot receive from the watch server"))
+                }
+            };
+
+            let json: serde_json::Value =
+                serde_json::from_str(&text).context(Error::InvalidResponse)?;
+            let data = &json["data"];
+            match json_extract!(json, as_str, ["type"]) {
+                "ping" => self.send(r#"{"type":"pong"}"#).await?,
+                "seat" => {
+                    let interval =
+                        Duration::from_secs(json_extract!(data, as_u64, ["keepIntervalSec"]));
+                    self.keep_seat = Some(tokio::time::interval_at(
+                        tokio::time::Instant::now() + interval,
+                        interval,
+                    ));
+                }
+                "stream" => {
+                    let mut available_qualities = Vec::new();
+                    if let Some(qualities) =
+                        json_extract_optional!(data, as_array, ["availableQualities"])
+                    {
+                        for quality in qualities {
+                            available_qualities.push(
+                                quality
+                                    .as_str()
+                                    .context("`quality` is not a string")
+                                    .context(Error::InvalidResponse)?
+                                    .to_owned(),
+                            );
+                        }
+                    }
+                    return Ok(Some(WatchEvent::Stream(StreamInfo {
+                        uri: json_extract!(data, as_string, ["uri"]),
+                        quality: json_extract!(data, as_string, ["quality"]),
+                        available_qualities,
+                    })));
+                }
+                "room" => {
+                    return Ok(Some(WatchEvent::Room(Room {
+                        name: json_extract!(data, as_string, ["name"]),
+                        message_server_uri: json_extract!(
+                            data,
+                            as_string,
+                            ["messageServer"]["uri"]
+                        ),
+                        thread_id: json_extract!(data, as_str, ["threadId"])
+                            .parse()
+                            .context(Error::InvalidResponse)?,
+                        post_key: json_extract_optional!(data, as_string, ["yourPostKey"]),
+                        wayback_key: json_extract_optional!(data, as_string, ["waybackkey"]),
+                    })));
+                }
+                "statistics" => {
+                    return Ok(Some(WatchEvent::Statistics(Statistics {
+                        viewers: json_extract!(data, as_u64, ["viewers"]),
+                        comments: json_extract!(data, as_u64, ["comments"]),
+                        ad_points: json_extract_optional!(data, as_u64, ["adPoints"]),
+                        gift_points: json_extract_optional!(data, as_u64, ["giftPoints"]),
+                    })));
+                }
+                "disconnect" => {
+                    let reason = json_extract!(data, as_str, ["reason"]);
+                    return Ok(Some(WatchEvent::Disconnect(DisconnectReason::parse(
+                        reason,
+                    ))));
+                }
+                "error" => {
+                    let code = json_extract!(data, as_str, ["code"]);
+                    bail!(anyhow!("the watch server returned an error: {}", code)
+                        .context(Error::InvalidResponse));
+                }
+                // such as `serverTime`, `schedule` and `akashic`
+                _ => {}
+            }
+        }
+    }
+    async fn send(&mut self, text: &str) -> Result<()> {
+        self.ws
+            .send(Message::Text(text.to_owned()))
+            .await
+            .context("cannot send to the watch server")
+    }
+}
@@ -0,0 +1,168 @@
+use super::{MylistId, MylistSettings};
+use crate::*;
+
+/// Creates a mylist. See also [`mylist::create`](super::create).
+pub(crate) async fn create(session: &Session, settings: &MylistSettings) -> Result<MylistId> {
+    let url = gen_url(session, "")?;
+    let request = session
+        .post(&url, request_options())
+        .form(&settings_form(settings));
+    let json = send(session, request, Some(Limit::Mylists))
+        .await
+        .context("cannot create the mylist")?;
+    Ok(MylistId(json_extract!(json, as_u64, ["data"]["mylistId"])))
+}
+/// Updates the settings of the mylist. See also [`MylistId::update`](super::MylistId::update).
+pub(crate) async fn update(
+    session: &Session,
+    id: MylistId,
+    settings: &MylistSettings,
+) -> Result<()> {
+    let url = gen_url(session, &format!("/{}", id))?;
+    let request = session
+        .put(&url, request_options())
+        .form(&settings_form(settings));
+    send(session, request, None)
+        .await
+        .context("cannot update the mylist")?;
+    Ok(())
+}
+/// Deletes the mylist. See also [`MylistId::delete`](super::MylistId::delete).
+pub(crate) async fn delete(session: &Session, id: MylistId) -> Result<()> {
+    let url = gen_url(session, &format!("/{}", id))?;
+    send(session, session.delete(&url, request_options()), None)
+        .await
+        .context("cannot delete the mylist")?;
+    Ok(())
+}
+/// Adds the video to the mylist. See also [`MylistId::add_item`](super::MylistId::add_item).
+pub(crate) async fn add_item(
+    session: &Session,
+    id: MylistId,
+    video: &VideoId,
+    memo: &str,
+) -> Result<()> {
+    let url = gen_url(session, &format!("/{}/items", id))?;
+    let request = session
+        .post(&url, request_options())
+        .query(&[("itemId", video.as_str()), ("description", memo)]);
+    send(session, request, Some(Limit::Items))
+        .await
+        .context("cannot add the video to the mylist")?;
+    Ok(())
+}
+/// Removes the items from the mylist. See also [`MylistId::remove_items`](super::MylistId::remove_items).
+pub(crate) async fn remove_items(session: &Session, id: MylistId, item_ids: &[u64]) -> Result<()> {
+    let url = gen_url(session, &format!("/{}/items", id))?;
+    let request = session
+        .delete(&url, request_options())
+        .query(&[("itemIds", join(item_ids))]);
+    send(session, request, None)
+        .await
+        .context("cannot remove the items from the mylist")?;
+    Ok(())
+}
+/// Copies or moves the items to another mylist.
+/// See also [`MylistId::copy_items_to`](super::MylistId::copy_items_to) and [`MylistId::move_items_to`](super::MylistId::move_items_to).
+pub(crate) async fn transfer_items(
+    session: &Session,
+    from: MylistId,
+    to: MylistId,
+    item_ids: &[u64],
+    is_move: bool,
+) -> Result<()> {
+    let action = if is_move { "move-to" } else { "copy-to" };
+    let url = gen_url(session, &format!("/{}/{}/{}", from, action, to))?;
+    let request = session
+        .post(&url, request_options())
+        .query(&[("itemIds", join(item_ids))]);
+    send(session, request, Some(Limit::Items))
+        .await
+        .with_context(|| format!("cannot {} the items", if is_move { "move" } else { "copy" }))?;
+    Ok(())
+}
+
+/// The limit a request may reach.
+#[derive(Debug, Clone, Copy)]
+enum Limit {
+    /// The number of the mylists of the login user.
+    Mylists,
+    /// The number of the items of the mylist.
+    Items,
+}
+
+/// Sends the request and checks the status.
+/// If `limit` is reached, the corresponding error is returned with the error code as its cause.
+async fn send(
+    session: &Session,
+    request: reqwest::RequestBuilder,
+    limit: Option<Limit>,
+) -> Result<serde_json::Value> {
+    let json = session.send_json(request).await?;
+
+    let status = json_extract!(json, as_u64, ["meta"]["status"]);
+    match status {
+        200 | 201 => Ok(json),
+        401 => bail!(Error::LoginRequired),
+        _ => {
+            let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+            let error = anyhow!("{} {}", status, error_code);
+            match (limit, error_code) {
+                (Some(Limit::Mylists), "MAX_MYLIST_COUNT_EXCEEDED_NON_PREMIUM") => {
+                    bail!(error.context(Error::MylistLimitReached { is_premium: false }))
+                }
+                (Some(Limit::Mylists), "MAX_MYLIST_COUNT_EXCEEDED_PREMIUM") => {
+                    bail!(error.context(Error::MylistLimitReached { is_premium: true }))
+                }
+                (Some(Limit::Items), "MAX_ITEM_COUNT_EXCEEDED_NON_PREMIUM") => {
+                    bail!(error.context(Error::MylistItemLimitReached { is_premium: false }))
+                }
+                (Some(Limit::Items), "MAX_ITEM_COUNT_EXCEEDED_PREMIUM") => {
+                    bail!(error.context(Error::MylistItemLimitReached { is_premium: true }))
+                }
+                _ => bail!(error.context(Error::InvalidResponse)),
+            }
+        }
+    }
+}
+
+fn settings_form(settings: &MylistSettings) -> Vec<(&'static str, String)> {
+    vec![
+        ("name", settings.name.clone()),
+        ("description", settings.description.clone()),
+        ("isPublic", settings.is_public.to_string()),
+        (
+            "defaultSortKey",
+            settings.default_sort.key.as_str().to_owned(),
+        ),
+        (
+            "defaultSortOrder",
+            settings.default_sort.order.as_str().to_owned(),
+        ),
+    ]
+}
+
+fn join(item_ids: &[u64]) -> String {
+    item_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn request_options() -> RequestOptions {
+    RequestOptions {
+        header_x_frontend_id: true,
+        header_x_request_with: true,
+        ..Default::default()
+    }
+}
+
+fn gen_url(session: &Session, path: &str) -> Result<String> {
+    ensure!(session.is_logged_in(), Error::LoginRequired);
+    Ok(format!(
+        "{}/v1/users/me/mylists{}",
+        session.endpoints().nvapi,
+        path
+    ))
+}
@@ -0,0 +1,404 @@
+use crate::*;
+
+mod edit;
+pub mod mylist_item;
+
+/// Creates a mylist of the login user and returns its ID.
+///
+/// # Errors
+/// - `Error::LoginRequired` if the session is not logged in.
+/// - `Error::MylistLimitReached` if the login user cannot create any more mylists.
+///
+/// # Examples
+/// ```no_run
+/// # use niconico::{*, mylist::*};
+/// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// # let mut session = Session::new(USER_AGENT, Language::Japanese);
+/// # session.set_cookie_user_session(&std::env::var("NICO_SID")?);
+/// let id = mylist::create(&session, &MylistSettings::new("favorites")).await?;
+/// id.add_item(&session, &"sm9".parse()?, "the first video").await?;
+///
+/// id.update(&session, &MylistSettings { is_public: true, ..MylistSettings::new("public favorites") }).await?;
+/// id.delete(&session).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn create(session: &Session, settings: &MylistSettings) -> Result<MylistId> {
+    edit::create(session, settings).await
+}
+
+/// Represents the settings of a mylist, used to create or update it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MylistSettings {
+    /// The name of the mylist.
+    pub name: String,
+    /// The description of the mylist.
+    pub description: String,
+    /// Whether the mylist is public.
+    pub is_public: bool,
+    /// The order of the items shown by default.
+    pub default_sort: MylistSort,
+}
+impl MylistSettings {
+    /// Creates settings of a private mylist with the name, no description and the default order.
+    pub fn new(name: impl Into<String>) -> MylistSettings {
+        MylistSettings {
+            name: name.into(),
+            description: String::new(),
+            is_public: false,
+            default_sort: MylistSort::default(),
+        }
+    }
+}
+
+/// Represents the ID of a mylist.
+///
+/// # Examples
+/// ```
+/// # use niconico::mylist::MylistId;
+/// let id: MylistId = "12345".parse()?;
+/// assert_eq!(id.as_u64(), 12345);
+///
+/// let id: MylistId = "https://www.nicovideo.jp/user/1/mylist/12345?ref=pc_userpage".parse()?;
+/// assert_eq!(id.as_u64(), 12345);
+/// assert_eq!(id.to_string(), "12345");
+///
+/// assert!("https://www.nicovideo.jp/user/1".parse::<MylistId>().is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct MylistId(u64);
+impl MylistId {
+    /// Creates an ID from the ID number.
+    pub fn new(id: u64) -> MylistId {
+        MylistId(id)
+    }
+    /// Returns the ID number.
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+    /// Gets the url of the mylist page.
+    pub fn mylist_url(self) -> String {
+        format!("https://www.nicovideo.jp/mylist/{}", self.0)
+    }
+    /// Fetches the items of this mylist.
+    ///
+    /// # Errors
+    /// The stream yields `Error::PrivateList` if this mylist is private and not owned by the login user.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use niconico::{*, mylist::*};
+    /// use futures::StreamExt;
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let session = Session::new(USER_AGENT, Language::Japanese);
+    /// let id: MylistId = "https://www.nicovideo.jp/mylist/12345".parse()?;
+    /// let mut items = id.stream_items(&session, MylistSort::default());
+    /// println!("{} items", items.len().await?);
+    /// while let Some(item) = items.next().await {
+    ///     let item = item?;
+    ///     println!("{}: {}", item.video.id, item.video.title);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stream_items(
+        self,
+        session: &Session,
+        sort: MylistSort,
+    ) -> mylist_item::MylistItemStream<'_> {
+        mylist_item::MylistItemStream::new(session, self, sort)
+    }
+
+    /// Updates the settings of this mylist. This mylist must be owned by the login user.
+    /// See [`create`] for examples.
+    ///
+    /// # Errors
+    /// `Error::LoginRequired` if the session is not logged in.
+    pub async fn update(self, session: &Session, settings: &MylistSettings) -> Result<()> {
+        edit::update(session, self, settings).await
+    }
+    /// Deletes this mylist. This mylist must be owned by the login user.
+    /// See [`create`] for examples.
+    ///
+    /// # Errors
+    /// `Error::LoginRequired` if the session is not logged in.
+    pub async fn delete(self, session: &Session) -> Result<()> {
+        edit::delete(session, self).await
+    }
+    /// Adds the video to this mylist with the memo. This mylist must be owned by the login user.
+    /// See [`create`] for examples.
+    ///
+    /// # Errors
+    /// - `Error::LoginRequired` if the session is not logged in.
+    /// - `Error::MylistItemLimitReached` if this mylist cannot have any more items.
+    pub async fn add_item(self, session: &Session, video: &VideoId, memo: &str) -> Result<()> {
+        edit::add_item(session, self, video, memo).await
+    }
+    /// Removes the items from this mylist. This mylist must be owned by the login user.
+    ///
+    /// `item_ids` are [`MylistItem::item_id`](mylist_item::MylistItem::item_id)s.
+    ///
+    /// # Errors
+    /// `Error::LoginRequired` if the session is not logged in.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use niconico::{*, mylist::*};
+    /// use futures::TryStreamExt;
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let mut session = Session::new(USER_AGENT, Language::Japanese);
+    /// # session.set_cookie_user_session(&std::env::var("NICO_SID")?);
+    /// let (from, to) = (MylistId::new(1), MylistId::new(2));
+    /// let deleted: Vec<u64> = from
+    ///     .stream_items(&session, MylistSort::default())
+    ///     .try_filter(|item| futures::future::ready(item.video.title.is_empty()))
+    ///     .map_ok(|item| item.item_id)
+    ///     .try_collect()
+    ///     .await?;
+    /// from.remove_items(&session, &deleted).await?;
+    ///
+    /// let all: Vec<u64> = from
+    ///     .stream_items(&session, MylistSort::default())
+    ///     .map_ok(|item| item.item_id)
+    ///     .try_collect()
+    ///     .await?;
+    /// from.move_items_to(&session, to, &all).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn remove_items(self, session: &Session, item_ids: &[u64]) -> Result<()> {
+        edit::remove_items(session, self, item_ids).await
+    }
+    /// Copies the items of this mylist to another mylist. Both mylists must be owned by the login user.
+    /// See [`remove_items`](MylistId::remove_items) for examples.
+    ///
+    /// # Errors
+    /// - `Error::LoginRequired` if the session is not logged in.
+    /// - `Error::MylistItemLimitReached` if `to` cannot have any more items.
+    pub async fn copy_items_to(
+        self,
+        session: &Session,
+        to: MylistId,
+        item_ids: &[u64],
+    ) -> Result<()> {
+        edit::transfer_items(session, self, to, item_ids, false).await
+    }
+    /// Moves the items of this mylist to another mylist. Both mylists must be owned by the login user.
+    /// See [`remove_items`](MylistId::remove_items) for examples.
+    ///
+    /// # Errors
+    /// - `Error::LoginRequired` if the session is not logged in.
+    /// - `Error::MylistItemLimitReached` if `to` cannot have any more items.
+    pub async fn move_items_to(
+        self,
+        session: &Session,
+        to: MylistId,
+        item_ids: &[u64],
+    ) -> Result<()> {
+        edit::transfer_items(session, self, to, item_ids, true).await
+    }
+}
+impl std::str::FromStr for MylistId {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // accepts mylist urls such as `https://www.nicovideo.jp/mylist/123` or `https://www.nicovideo.jp/user/1/mylist/123`
+        let mut segments = s
+            .split(['?', '#'].as_ref())
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches('/')
+            .rsplit('/');
+        let id = segments.next().unwrap_or_default();
+        let is_valid = matches!(segments.next(), None | Some("mylist"))
+            && !id.is_empty()
+            && id.bytes().all(|b| b.is_ascii_digit());
+        ensure!(
+            is_valid,
+            anyhow!("`{}` is not a mylist id", s).context(Error::InvalidId)
+        );
+
+        Ok(MylistId(id.parse().context(Error::InvalidId)?))
+    }
+}
+impl std::fmt::Display for MylistId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Represents a mylist.
+#[derive(Debug, Clone)]
+pub struct Mylist {
+    /// The ID of this mylist.
+    pub id: MylistId,
+    /// The name of this mylist.
+    pub name: String,
+    /// The description of this mylist.
+    pub description: String,
+    /// Whether this mylist is public.
+    pub is_public: bool,
+    /// The owner of this mylist.
+    pub owner: User,
+    /// The number of the items in this mylist.
+    pub item_count: u64,
+    /// The number of users who follow this mylist.
+    pub follower_count: u64,
+    /// The order of the items the owner has chosen.
+    pub default_sort: MylistSort,
+    /// The date when this mylist was created.
+    pub created_at: DateTime<FixedOffset>,
+    /// Whether the login user is following this mylist.
+    pub is_following: bool,
+}
+impl Mylist {
+    /// Fetches the mylists of the user. See also [`User::fetch_mylists`].
+    pub async fn fetch_list(session: &Session, user: User) -> Result<Vec<Mylist>> {
+        let url = format!(
+            "{}/v1/users/{}/mylists?sampleItemCount=0",
+            session.endpoints().nvapi,
+            user.nvapi_id()
+        );
+        let json = session
+            .get_json(
+                &url,
+                RequestOptions {
+                    header_x_frontend_id: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .context("cannot fetch mylists")
+            .context(Error::InvalidResponse)?;
+
+        let status = json_extract!(json, as_u64, ["meta"]["status"]);
+        match status {
+            200 => (),
+            401 => bail!(Error::LoginRequired),
+            403 => bail!(Error::PrivateList),
+            _ => {
+                let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+                bail!(anyhow!("{} {}", status, error_code).context(Error::InvalidResponse));
+            }
+        }
+
+        let mut mylists = Vec::new();
+        for mylist in json_extract!(json, as_array, ["data"]["mylists"]) {
+            mylists.push(Mylist::from_json(mylist)?);
+        }
+        Ok(mylists)
+    }
+    pub(crate) fn from_json(json: &serde_json::Value) -> Result<Mylist> {
+        Ok(Mylist {
+            id: MylistId(json_extract!(json, as_u64, ["id"])),
+            name: json_extract!(json, as_string, ["name"]),
+            description: json_extract!(json, as_string, ["description"]),
+            is_public: json_extract!(json, as_bool, ["isPublic"]),
+            owner: User::UserId(
+                json_extract!(json, as_str, ["owner"]["id"])
+                    .parse()
+                    .context(Error::InvalidResponse)?,
+            ),
+            item_count: json_extract!(json, as_u64, ["itemsCount"]),
+            follower_count: json_extract!(json, as_u64, ["followerCount"]),
+            default_sort: MylistSort {
+                key: json_extract!(json, as_str, ["defaultSortKey"]).parse()?,
+                order: match json_extract!(json, as_str, ["defaultSortOrder"]) {
+                    "asc" => SortOrder::Ascending,
+                    _ => SortOrder::Descending,
+                },
+            },
+            created_at: DateTime::<FixedOffset>::parse_from_rfc3339(json_extract!(
+                json,
+                as_str,
+                ["createdAt"]
+            ))
+            .context(Error::InvalidResponse)?,
+            is_following: json_extract!(json, as_bool, ["isFollowing"]),
+        })
+    }
+}
+
+/// Represents an order of the items of a mylist.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct MylistSort {
+    /// What the items are sorted by.
+    pub key: MylistSortKey,
+    /// Whether the items are sorted in ascending or descending order.
+    pub order: SortOrder,
+}
+impl Default for MylistSort {
+    /// Newest added first.
+    fn default() -> Self {
+        MylistSort {
+            key: MylistSortKey::AddedAt,
+            order: SortOrder::Descending,
+        }
+    }
+}
+/// Represents what the items of a mylist are sorted by.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MylistSortKey {
+    /// The date when the item was added.
+    AddedAt,
+    /// The title of the video.
+    Title,
+    /// The memo of the item.
+    Memo,
+    /// The date when the video was uploaded.
+    RegisteredAt,
+    /// The number of views of the video.
+    ViewCount,
+    /// The date when the last comment was posted on the video.
+    LastCommentedAt,
+    /// The number of comments on the video.
+    CommentCount,
+    /// The number of likes of the video.
+    LikeCount,
+    /// The number of mylists the video is registered to.
+    MylistCount,
+    /// The length of the video.
+    Duration,
+}
+impl MylistSortKey {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            MylistSortKey::AddedAt => "addedAt",
+            MylistSortKey::Title => "title",
+            MylistSortKey::Memo => "mylistComment",
+            MylistSortKey::RegisteredAt => "registeredAt",
+            MylistSortKey::ViewCount => "viewCount",
+            MylistSortKey::LastCommentedAt => "lastCommentTime",
+            MylistSortKey::CommentCount => "commentCount",
+            MylistSortKey::LikeCount => "likeCount",
+            MylistSortKey::MylistCount => "mylistCount",
+            MylistSortKey::Duration => "duration",
+        }
+    }
+}
+impl std::str::FromStr for MylistSortKey {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "addedAt" => Ok(MylistSortKey::AddedAt),
+            "title" => Ok(MylistSortKey::Title),
+            "mylistComment" => Ok(MylistSortKey::Memo),
+            "registeredAt" => Ok(MylistSortKey::RegisteredAt),
+            "viewCount" => Ok(MylistSortKey::ViewCount),
+            "lastCommentTime" => Ok(MylistSortKey::LastCommentedAt),
+            "commentCount" => Ok(MylistSortKey::CommentCount),
+            "likeCount" => Ok(MylistSortKey::LikeCount),
+            "mylistCount" => Ok(MylistSortKey::MylistCount),
+            "duration" => Ok(MylistSortKey::Duration),
+            s => {
+                bail!(anyhow!("unknown mylist sort key: `{}`", s).context(Error::InvalidResponse))
+            }
+        }
+    }
+}
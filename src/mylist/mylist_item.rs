@@ -0,0 +1,159 @@
+use super::{MylistId, MylistSort};
+use crate::*;
+use std::collections::VecDeque;
+use video::summary::VideoSummary;
+
+/// Represents an item of a mylist.
+#[derive(Debug, Clone)]
+pub struct MylistItem {
+    /// The ID number of this item, which is unique in the mylist.
+    pub item_id: u64,
+    /// The video of this item.
+    pub video: VideoSummary,
+    /// The memo the owner of the mylist wrote on this item.
+    pub memo: String,
+    /// The date when this item was added.
+    pub added_at: DateTime<FixedOffset>,
+}
+impl MylistItem {
+    pub(crate) fn from_json(json: &serde_json::Value) -> Result<MylistItem> {
+        Ok(MylistItem {
+            item_id: json_extract!(json, as_u64, ["itemId"]),
+            video: VideoSummary::from_json(&json["video"])?,
+            memo: json_extract!(json, as_string, ["description"]),
+            added_at: DateTime::<FixedOffset>::parse_from_rfc3339(json_extract!(
+                json,
+                as_str,
+                ["addedAt"]
+            ))
+            .context(Error::InvalidResponse)?,
+        })
+    }
+}
+
+type FetchMylistItemFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<(VecDeque<MylistItem>, bool, usize)>> + 'a>>;
+
+/// Streams items of a mylist.
+pub struct MylistItemStream<'a> {
+    session: &'a Session,
+    id: MylistId,
+    sort: MylistSort,
+    future: Option<FetchMylistItemFuture<'a>>,
+    buf: VecDeque<MylistItem>,
+    is_finished: bool,
+    next_page: u32,
+    len: Option<usize>,
+}
+impl<'a> Stream for MylistItemStream<'a> {
+    type Item = Result<MylistItem>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        if self.buf.is_empty() && self.future.is_none() && !self.is_finished {
+            self.future = Some(Box::pin(Self::gen_future(
+                self.session,
+                self.id,
+                self.sort,
+                self.next_page,
+            )));
+        }
+        if let Some(future) = self.future.as_mut() {
+            match future.as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => {
+                    self.future = None;
+                    match result {
+                        Ok((buf, is_finished, len)) => {
+                            self.buf = buf;
+                            self.is_finished = is_finished;
+                            self.next_page += 1;
+                            self.len = Some(len);
+                        }
+                        Err(err) => return Poll::Ready(Some(Err(err))),
+                    }
+                }
+            }
+        }
+
+        Poll::Ready(Ok(self.buf.pop_front()).transpose())
+    }
+}
+impl<'a> MylistItemStream<'a> {
+    pub fn new(session: &Session, id: MylistId, sort: MylistSort) -> MylistItemStream<'_> {
+        MylistItemStream {
+            session,
+            id,
+            sort,
+            future: None,
+            buf: VecDeque::new(),
+            is_finished: false,
+            next_page: 1,
+            len: None,
+        }
+    }
+    async fn gen_future(
+        session: &'a Session,
+        id: MylistId,
+        sort: MylistSort,
+        page: u32,
+    ) -> Result<(VecDeque<MylistItem>, bool, usize)> {
+        let url = gen_url(&session.endpoints().nvapi, id, sort, page);
+        let json = session
+            .get_json(
+                &url,
+                RequestOptions {
+                    header_x_frontend_id: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .context("cannot fetch mylist items")
+            .context(Error::InvalidResponse)?;
+
+        let status = json_extract!(json, as_u64, ["meta"]["status"]);
+        match status {
+            200 => (),
+            401 => bail!(Error::LoginRequired),
+            403 => bail!(Error::PrivateList),
+            _ => {
+                let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+                bail!(anyhow!("{} {}", status, error_code).context(Error::InvalidResponse));
+            }
+        }
+
+        let mut items = VecDeque::new();
+        for item in json_extract!(json, as_array, ["data"]["mylist"]["items"]) {
+            items.push_back(MylistItem::from_json(item)?);
+        }
+
+        let len = json_extract!(json, as_u64, ["data"]["mylist"]["totalItemCount"]) as usize;
+        let is_finished = !json_extract!(json, as_bool, ["data"]["mylist"]["hasNext"]);
+
+        Ok((items, is_finished, len))
+    }
+    /// Fetches the number of the items.
+    pub async fn len(&mut self) -> Result<usize> {
+        // length is written in all response.
+        // if not received any response yet, do the first request.
+        if self.len.is_none() {
+            let (buf, is_finished, len) =
+                Self::gen_future(self.session, self.id, self.sort, self.next_page).await?;
+            self.buf = buf;
+            self.is_finished = is_finished;
+            self.next_page += 1;
+            self.len = Some(len);
+        }
+
+        Ok(self.len.unwrap())
+    }
+}
+
+fn gen_url(nvapi: &str, id: MylistId, sort: MylistSort, page: u32) -> String {
+    format!(
+        "{}/v2/mylists/{}?pageSize=100&page={}&sortKey={}&sortOrder={}",
+        nvapi,
+        id,
+        page,
+        sort.key.as_str(),
+        sort.order.as_str()
+    )
+}
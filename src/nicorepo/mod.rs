@@ -24,7 +24,7 @@ pub fn stream(
     session: &Session,
     content_filter: ContentFilter,
     sender_filter: SenderFilter,
-) -> nicorepo_stream::NicorepoStream {
+) -> nicorepo_stream::NicorepoStream<'_> {
     nicorepo_stream::NicorepoStream::new(session, content_filter, sender_filter)
 }
 
@@ -50,7 +50,7 @@ impl<'a> NicorepoStream<'a> {
         session: &'a Session,
         content_filter: ContentFilter,
         sender_filter: SenderFilter,
-    ) -> NicorepoStream {
+    ) -> NicorepoStream<'a> {
         NicorepoStream {
             session,
             content_filter,
@@ -69,7 +69,12 @@ impl<'a> NicorepoStream<'a> {
         sender_filter: SenderFilter,
         last_item_id: Option<String>,
     ) -> Result<(VecDeque<NicorepoItem>, bool)> {
-        let url = gen_url(content_filter, sender_filter, last_item_id);
+        let url = gen_url(
+            &session.endpoints().public_api,
+            content_filter,
+            sender_filter,
+            last_item_id,
+        );
         let json = session
             .get_json(&url, None)
             .await
@@ -100,6 +105,7 @@ impl<'a> NicorepoStream<'a> {
 }
 
 fn gen_url(
+    public_api: &str,
     content_filter: ContentFilter,
     sender_filter: SenderFilter,
     last_item_id: Option<String>,
@@ -146,9 +152,10 @@ fn gen_url(
         }
     }
 
-    let mut url =
-        "https://public.api.nicovideo.jp/v1/timelines/nicorepo/last-1-month/my/pc/entries.json"
-            .to_owned();
+    let mut url = format!(
+        "{}/v1/timelines/nicorepo/last-1-month/my/pc/entries.json",
+        public_api
+    );
     if !params.is_empty() {
         url += "?";
     }
@@ -0,0 +1,236 @@
+//! Fetches the video rankings.
+
+use crate::*;
+
+pub mod ranking_stream;
+
+/// Creates stream of the ranked videos, in order of rank.
+///
+/// If `tag` is specified, the ranking is limited to the videos with the tag.
+/// Tags which have their own rankings can be fetched with [`fetch_popular_tags`].
+///
+/// # Examples
+/// ```no_run
+/// use futures::StreamExt;
+/// # use niconico::{*, ranking::*};
+/// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// # let session = Session::new(USER_AGENT, Language::Japanese);
+/// let mut videos = ranking::stream(&session, Genre::All, Term::Day, None).take(10);
+/// while let Some(video) = videos.next().await {
+///     let video = video?;
+///     println!("{}. {}", video.rank, video.video.title);
+/// }
+///
+/// let tags = ranking::fetch_popular_tags(&session, Genre::Game).await?;
+/// let mut videos = ranking::stream(&session, Genre::Game, Term::Week, Some(&tags[0]));
+/// # Ok(())
+/// # }
+/// ```
+pub fn stream<'a>(
+    session: &'a Session,
+    genre: Genre,
+    term: Term,
+    tag: Option<&str>,
+) -> ranking_stream::RankingStream<'a> {
+    ranking_stream::RankingStream::new(session, genre, term, tag.map(|tag| tag.to_owned()))
+}
+
+/// Fetches the tags which have their own rankings in the genre. See [`stream`] for examples.
+///
+/// [`Genre::All`] and [`Genre::HotTopic`] have no popular tags.
+pub async fn fetch_popular_tags(session: &Session, genre: Genre) -> Result<Vec<String>> {
+    let url = format!(
+        "{}/v1/genres/{}/popular-tags",
+        session.endpoints().nvapi,
+        genre.key()
+    );
+    let json = session
+        .get_json(
+            &url,
+            RequestOptions {
+                cookie_user_session: false,
+                header_x_frontend_id: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .context("cannot fetch popular tags")
+        .context(Error::InvalidResponse)?;
+
+    let status = json_extract!(json, as_u64, ["meta"]["status"]);
+    if status != 200 {
+        let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+        bail!(anyhow!("{} {}", status, error_code).context(Error::InvalidResponse));
+    }
+
+    let mut tags = Vec::new();
+    for tag in json_extract!(json, as_array, ["data"]["tags"]) {
+        tags.push(
+            tag.as_str()
+                .context("`tag` is not a string")
+                .context(Error::InvalidResponse)?
+                .to_owned(),
+        );
+    }
+    Ok(tags)
+}
+
+/// Represents a genre of the rankings.
+///
+/// # Examples
+/// ```
+/// # use niconico::ranking::Genre;
+/// let genre: Genre = "4eet3ca4".parse()?;
+/// assert_eq!(genre, Genre::Game);
+/// assert_eq!(genre.name(), "ゲーム");
+/// assert_eq!(Genre::HotTopic.key(), "hot-topic");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Genre {
+    /// All genres. (全ジャンル)
+    All,
+    /// The videos which are rapidly getting popular. (話題)
+    HotTopic,
+    /// エンターテイメント
+    Entertainment,
+    /// ラジオ
+    Radio,
+    /// 音楽・サウンド
+    MusicSound,
+    /// ダンス
+    Dance,
+    /// 動物
+    Animal,
+    /// 自然
+    Nature,
+    /// 料理
+    Cooking,
+    /// 旅行・アウトドア
+    TravelingOutdoor,
+    /// 乗り物
+    Vehicle,
+    /// スポーツ
+    Sports,
+    /// 社会・政治・時事
+    SocietyPoliticsNews,
+    /// 技術・工作
+    TechnologyCraft,
+    /// 解説・講座
+    CommentaryLecture,
+    /// アニメ
+    Anime,
+    /// ゲーム
+    Game,
+    /// その他
+    Other,
+}
+impl Genre {
+    /// All the genres.
+    pub const ALL: [Genre; 18] = [
+        Genre::All,
+        Genre::HotTopic,
+        Genre::Entertainment,
+        Genre::Radio,
+        Genre::MusicSound,
+        Genre::Dance,
+        Genre::Animal,
+        Genre::Nature,
+        Genre::Cooking,
+        Genre::TravelingOutdoor,
+        Genre::Vehicle,
+        Genre::Sports,
+        Genre::SocietyPoliticsNews,
+        Genre::TechnologyCraft,
+        Genre::CommentaryLecture,
+        Genre::Anime,
+        Genre::Game,
+        Genre::Other,
+    ];
+
+    /// Gets the key of the genre used in URLs, such as `4eet3ca4`.
+    pub fn key(self) -> &'static str {
+        match self {
+            Genre::All => "all",
+            Genre::HotTopic => "hot-topic",
+            Genre::Entertainment => "8kjl94ap",
+            Genre::Radio => "oxzi6bje",
+            Genre::MusicSound => "wq76qdin",
+            Genre::Dance => "6yuf530c",
+            Genre::Animal => "ne72lua2",
+            Genre::Nature => "l4wy3zaw",
+            Genre::Cooking => "p1acxuoz",
+            Genre::TravelingOutdoor => "h67gzba0",
+            Genre::Vehicle => "zzpkg0kl",
+            Genre::Sports => "4w3p65pf",
+            Genre::SocietyPoliticsNews => "lzicx0y6",
+            Genre::TechnologyCraft => "n46kcz9u",
+            Genre::CommentaryLecture => "d2um7mc4",
+            Genre::Anime => "zc49b03a",
+            Genre::Game => "4eet3ca4",
+            Genre::Other => "ramuboyn",
+        }
+    }
+    /// Gets the Japanese name of the genre.
+    pub fn name(self) -> &'static str {
+        match self {
+            Genre::All => "全ジャンル",
+            Genre::HotTopic => "話題",
+            Genre::Entertainment => "エンターテイメント",
+            Genre::Radio => "ラジオ",
+            Genre::MusicSound => "音楽・サウンド",
+            Genre::Dance => "ダンス",
+            Genre::Animal => "動物",
+            Genre::Nature => "自然",
+            Genre::Cooking => "料理",
+            Genre::TravelingOutdoor => "旅行・アウトドア",
+            Genre::Vehicle => "乗り物",
+            Genre::Sports => "スポーツ",
+            Genre::SocietyPoliticsNews => "社会・政治・時事",
+            Genre::TechnologyCraft => "技術・工作",
+            Genre::CommentaryLecture => "解説・講座",
+            Genre::Anime => "アニメ",
+            Genre::Game => "ゲーム",
+            Genre::Other => "その他",
+        }
+    }
+}
+impl std::str::FromStr for Genre {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Genre::ALL
+            .iter()
+            .copied()
+            .find(|genre| genre.key() == s)
+            .with_context(|| format!("unknown genre: `{}`", s))
+            .context(Error::InvalidId)
+    }
+}
+
+/// Represents a period of a ranking.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Term {
+    /// The last hour. (毎時)
+    Hour,
+    /// The last 24 hours. (24時間)
+    Day,
+    /// The last week. (週間)
+    Week,
+    /// The last month. (月間)
+    Month,
+    /// All time. (全期間)
+    Total,
+}
+impl Term {
+    fn as_str(self) -> &'static str {
+        match self {
+            Term::Hour => "hour",
+            Term::Day => "24h",
+            Term::Week => "week",
+            Term::Month => "month",
+            Term::Total => "total",
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use super::{Genre, Term};
+use crate::*;
+use std::collections::VecDeque;
+use video::summary::VideoSummary;
+
+/// Represents a video in a ranking.
+#[derive(Debug, Clone)]
+pub struct RankedVideo {
+    /// The rank, starting from 1.
+    pub rank: u64,
+    /// The video.
+    pub video: VideoSummary,
+}
+
+type FetchRankedVideoFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<(VecDeque<VideoSummary>, bool)>> + 'a>>;
+
+/// Streams videos in a ranking. See also [ranking::stream()](super::stream).
+pub struct RankingStream<'a> {
+    session: &'a Session,
+    genre: Genre,
+    term: Term,
+    tag: Option<String>,
+    future: Option<FetchRankedVideoFuture<'a>>,
+    buf: VecDeque<VideoSummary>,
+    is_finished: bool,
+    next_page: u32,
+    next_rank: u64,
+}
+impl<'a> Stream for RankingStream<'a> {
+    type Item = Result<RankedVideo>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        if self.buf.is_empty() && self.future.is_none() && !self.is_finished {
+            self.future = Some(Box::pin(Self::gen_future(
+                self.session,
+                self.genre,
+                self.term,
+                self.tag.clone(),
+                self.next_page,
+            )));
+        }
+        if let Some(future) = self.future.as_mut() {
+            match future.as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => {
+                    self.future = None;
+                    match result {
+                        Ok((buf, is_finished)) => {
+                            self.buf = buf;
+                            self.is_finished = is_finished;
+                            self.next_page += 1;
+                        }
+                        Err(err) => return Poll::Ready(Some(Err(err))),
+                    }
+                }
+            }
+        }
+
+        let video = self.buf.pop_front().map(|video| {
+            let rank = self.next_rank;
+            self.next_rank += 1;
+            RankedVideo { rank, video }
+        });
+        Poll::Ready(Ok(video).transpose())
+    }
+}
+impl<'a> RankingStream<'a> {
+    pub fn new(
+        session: &Session,
+        genre: Genre,
+        term: Term,
+        tag: Option<String>,
+    ) -> RankingStream<'_> {
+        RankingStream {
+            session,
+            genre,
+            term,
+            tag,
+            future: None,
+            buf: VecDeque::new(),
+            is_finished: false,
+            next_page: 1,
+            next_rank: 1,
+        }
+    }
+
+    /// Returns `(videos, is_finished)`
+    async fn gen_future(
+        session: &'a Session,
+        genre: Genre,
+        term: Term,
+        tag: Option<String>,
+        page: u32,
+    ) -> Result<(VecDeque<VideoSummary>, bool)> {
+        let url = gen_url(&session.endpoints().nvapi, genre, term, tag, page)?;
+        let json = session
+            .get_json(
+                &url,
+                RequestOptions {
+                    cookie_user_session: false,
+                    header_x_frontend_id: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .context("cannot fetch the ranking")
+            .context(Error::InvalidResponse)?;
+
+        let status = json_extract!(json, as_u64, ["meta"]["status"]);
+        if status != 200 {
+            let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+            bail!(anyhow!("{} {}", status, error_code).context(Error::InvalidResponse));
+        }
+
+        let mut videos = VecDeque::new();
+        for video in json_extract!(json, as_array, ["data"]["items"]) {
+            videos.push_back(VideoSummary::from_json(video)?);
+        }
+
+        let is_finished = !json_extract!(json, as_bool, ["data"]["hasNext"]);
+
+        Ok((videos, is_finished))
+    }
+}
+
+fn gen_url(
+    nvapi: &str,
+    genre: Genre,
+    term: Term,
+    tag: Option<String>,
+    page: u32,
+) -> Result<String> {
+    let path = match genre {
+        Genre::HotTopic => format!("{}/v1/ranking/hot-topic", nvapi),
+        genre => format!("{}/v1/ranking/genre/{}", nvapi, genre.key()),
+    };
+    let mut params = vec![
+        ("term", term.as_str().to_owned()),
+        ("page", page.to_string()),
+    ];
+    if let Some(tag) = tag {
+        params.push(("tag", tag));
+    }
+
+    let url = reqwest::Url::parse_with_params(&path, &params).context("invalid nvapi endpoint")?;
+    Ok(url.into())
+}
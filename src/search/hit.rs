@@ -0,0 +1,86 @@
+use crate::*;
+use std::time::Duration;
+
+/// Represents a video found by the search.
+///
+/// Only the [fields](super::Field) specified in the query are filled. The others are `None`.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    /// The ID of the video.
+    pub id: Option<VideoId>,
+    /// The title of the video.
+    pub title: Option<String>,
+    /// The description of the video, decorated with HTML.
+    pub description: Option<String>,
+    /// The uploader of the video, if the video is uploaded by a user.
+    pub user: Option<User>,
+    /// The ID number of the channel, if the video is uploaded by a channel.
+    pub channel_id: Option<u64>,
+    /// The number of times the video has been viewed.
+    pub view_count: Option<u64>,
+    /// The number of mylists the video is registered to.
+    pub mylist_count: Option<u64>,
+    /// The number of likes (いいね！) of the video.
+    pub like_count: Option<u64>,
+    /// The number of comments on the video.
+    pub comment_count: Option<u64>,
+    /// The length of the video.
+    pub duration: Option<Duration>,
+    /// The URL of the thumbnail.
+    pub thumbnail_url: Option<String>,
+    /// The date when the video was uploaded.
+    pub registered_at: Option<DateTime<FixedOffset>>,
+    /// The date when the last comment was posted.
+    pub last_commented_at: Option<DateTime<FixedOffset>>,
+    /// The bodies of the latest comments, separated by spaces.
+    pub last_comment_body: Option<String>,
+    /// The tags of the video.
+    pub tags: Option<Vec<String>>,
+    /// The category tags of the video.
+    pub category_tags: Option<Vec<String>>,
+    /// The genre of the video.
+    pub genre: Option<String>,
+}
+impl SearchHit {
+    pub(crate) fn from_json(json: &serde_json::Value) -> Result<SearchHit> {
+        let date = |name: &str| -> Result<Option<DateTime<FixedOffset>>> {
+            json[name]
+                .as_str()
+                .map(|date| {
+                    DateTime::<FixedOffset>::parse_from_rfc3339(date)
+                        .with_context(|| format!("`{}` is invalid", name))
+                        .context(Error::InvalidResponse)
+                })
+                .transpose()
+        };
+        let words = |name: &str| -> Option<Vec<String>> {
+            json[name].as_str().map(|words| {
+                words
+                    .split_whitespace()
+                    .map(|word| word.to_owned())
+                    .collect()
+            })
+        };
+
+        Ok(SearchHit {
+            id: json["contentId"].as_str().map(str::parse).transpose()?,
+            title: json_extract_optional!(json, as_string, ["title"]),
+            description: json_extract_optional!(json, as_string, ["description"]),
+            user: json_extract_optional!(json, as_u64, ["userId"]).map(User::UserId),
+            channel_id: json_extract_optional!(json, as_u64, ["channelId"]),
+            view_count: json_extract_optional!(json, as_u64, ["viewCounter"]),
+            mylist_count: json_extract_optional!(json, as_u64, ["mylistCounter"]),
+            like_count: json_extract_optional!(json, as_u64, ["likeCounter"]),
+            comment_count: json_extract_optional!(json, as_u64, ["commentCounter"]),
+            duration: json_extract_optional!(json, as_u64, ["lengthSeconds"])
+                .map(Duration::from_secs),
+            thumbnail_url: json_extract_optional!(json, as_string, ["thumbnailUrl"]),
+            registered_at: date("startTime")?,
+            last_commented_at: date("lastCommentTime")?,
+            last_comment_body: json_extract_optional!(json, as_string, ["lastResBody"]),
+            tags: words("tags"),
+            category_tags: words("categoryTags"),
+            genre: json_extract_optional!(json, as_string, ["genre"]),
+        })
+    }
+}
@@ -0,0 +1,405 @@
+//! Searches videos with the snapshot search API v2.
+
+use crate::*;
+
+pub mod hit;
+pub mod search_stream;
+
+/// The number of hits fetched at once. This is the maximum of `_limit`.
+const PAGE_SIZE: usize = 100;
+/// The maximum of `_offset`. Hits beyond this cannot be fetched.
+const MAX_OFFSET: usize = 100_000;
+
+/// Creates stream of videos which match the query.
+///
+/// The snapshot search API is updated once a day, so the results may be up to a day old.
+/// ```no_run
+/// use futures::StreamExt;
+/// # use niconico::{*, search::*};
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// # let session = Session::new("user agent", Language::Japanese);
+/// let query = SearchQuery::new("初音ミク", "my-app")
+///     .targets(&[Target::TagsExact])
+///     .fields(&[Field::ContentId, Field::Title, Field::ViewCounter])
+///     .filter(Filter::at_least(Field::ViewCounter, 100000))
+///     .sort(Field::ViewCounter, SortOrder::Descending);
+/// let mut search_stream = search::search(&session, query);
+/// println!("{} videos", search_stream.len().await?);
+/// while let Some(hit) = search_stream.next().await {
+///     let hit = hit?;
+///     println!("{:?}: {:?}", hit.id, hit.title);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn search(session: &Session, query: SearchQuery) -> search_stream::SearchStream<'_> {
+    search_stream::SearchStream::new(session, query)
+}
+
+/// Represents a query of the search. See [`search()`] for examples.
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    keyword: String,
+    context: String,
+    targets: Vec<Target>,
+    fields: Vec<Field>,
+    filter: Option<Filter>,
+    sort: (Field, SortOrder),
+}
+impl SearchQuery {
+    /// Creates a query which searches `keyword` in titles, descriptions and tags.
+    ///
+    /// `keyword` supports the search syntax of niconico, such as `OR` and `-`.
+    /// `context` is the name of your application, which is sent to the API as `_context`.
+    ///
+    /// By default, all fields are fetched and the results are sorted by view counts in descending order.
+    pub fn new(keyword: impl Into<String>, context: impl Into<String>) -> SearchQuery {
+        SearchQuery {
+            keyword: keyword.into(),
+            context: context.into(),
+            targets: vec![Target::Title, Target::Description, Target::Tags],
+            fields: Field::ALL.to_vec(),
+            filter: None,
+            sort: (Field::ViewCounter, SortOrder::Descending),
+        }
+    }
+    /// Sets what the keyword is searched in.
+    pub fn targets(mut self, targets: &[Target]) -> SearchQuery {
+        self.targets = targets.to_vec();
+        self
+    }
+    /// Sets which fields of [`SearchHit`](hit::SearchHit) are fetched.
+    pub fn fields(mut self, fields: &[Field]) -> SearchQuery {
+        self.fields = fields.to_vec();
+        self
+    }
+    /// Adds a filter. If a filter is already set, the results must match both.
+    pub fn filter(mut self, filter: Filter) -> SearchQuery {
+        self.filter = Some(match self.filter.take() {
+            Some(Filter::And(mut filters)) => {
+                filters.push(filter);
+                Filter::And(filters)
+            }
+            Some(current) => Filter::And(vec![current, filter]),
+            None => filter,
+        });
+        self
+    }
+    /// Sets the order of the results.
+    ///
+    /// Only [`ViewCounter`](Field::ViewCounter), [`MylistCounter`](Field::MylistCounter),
+    /// [`LikeCounter`](Field::LikeCounter), [`LengthSeconds`](Field::LengthSeconds),
+    /// [`StartTime`](Field::StartTime), [`CommentCounter`](Field::CommentCounter) and
+    /// [`LastCommentTime`](Field::LastCommentTime) are supported.
+    pub fn sort(mut self, field: Field, order: SortOrder) -> SearchQuery {
+        self.sort = (field, order);
+        self
+    }
+
+    /// Generates the URL to fetch the hits from `offset`.
+    fn to_url(&self, search: &str, offset: usize) -> Result<String> {
+        let join = |values: Vec<&str>| values.join(",");
+        let mut params = vec![
+            ("q", self.keyword.clone()),
+            (
+                "targets",
+                join(self.targets.iter().map(|t| t.as_str()).collect()),
+            ),
+            (
+                "fields",
+                join(self.fields.iter().map(|f| f.as_str()).collect()),
+            ),
+            (
+                "_sort",
+                format!(
+                    "{}{}",
+                    match self.sort.1 {
+                        SortOrder::Ascending => "+",
+                        SortOrder::Descending => "-",
+                    },
+                    self.sort.0.as_str()
+                ),
+            ),
+            ("_offset", offset.to_string()),
+            ("_limit", PAGE_SIZE.to_string()),
+            ("_context", self.context.clone()),
+        ];
+        if let Some(filter) = &self.filter {
+            params.push(("jsonFilter", filter.to_json().to_string()));
+        }
+
+        let url = reqwest::Url::parse_with_params(
+            &format!("{}/api/v2/snapshot/video/contents/search", search),
+            &params,
+        )
+        .context("invalid search endpoint")?;
+        Ok(url.into())
+    }
+}
+
+/// Represents where the keyword is searched in.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Target {
+    /// The title.
+    Title,
+    /// The description.
+    Description,
+    /// The tags, matched partially.
+    Tags,
+    /// The tags, matched exactly. Cannot be combined with the other targets.
+    TagsExact,
+}
+impl Target {
+    fn as_str(self) -> &'static str {
+        match self {
+            Target::Title => "title",
+            Target::Description => "description",
+            Target::Tags => "tags",
+            Target::TagsExact => "tagsExact",
+        }
+    }
+}
+
+/// Represents a field of the search index.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Field {
+    /// The ID of the video.
+    ContentId,
+    /// The title.
+    Title,
+    /// The description.
+    Description,
+    /// The ID number of the uploader.
+    UserId,
+    /// The ID number of the channel.
+    ChannelId,
+    /// The number of views.
+    ViewCounter,
+    /// The number of mylists.
+    MylistCounter,
+    /// The number of likes.
+    LikeCounter,
+    /// The length in seconds.
+    LengthSeconds,
+    /// The URL of the thumbnail.
+    ThumbnailUrl,
+    /// The date when the video was uploaded.
+    StartTime,
+    /// The bodies of the latest comments.
+    LastResBody,
+    /// The number of comments.
+    CommentCounter,
+    /// The date when the last comment was posted.
+    LastCommentTime,
+    /// The category tags.
+    CategoryTags,
+    /// The tags.
+    Tags,
+    /// The genre.
+    Genre,
+}
+impl Field {
+    /// All the fields.
+    pub const ALL: [Field; 17] = [
+        Field::ContentId,
+        Field::Title,
+        Field::Description,
+        Field::UserId,
+        Field::ChannelId,
+        Field::ViewCounter,
+        Field::MylistCounter,
+        Field::LikeCounter,
+        Field::LengthSeconds,
+        Field::ThumbnailUrl,
+        Field::StartTime,
+        Field::LastResBody,
+        Field::CommentCounter,
+        Field::LastCommentTime,
+        Field::CategoryTags,
+        Field::Tags,
+        Field::Genre,
+    ];
+
+    /// Gets the name of the field used in the API, such as `viewCounter`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Field::ContentId => "contentId",
+            Field::Title => "title",
+            Field::Description => "description",
+            Field::UserId => "userId",
+            Field::ChannelId => "channelId",
+            Field::ViewCounter => "viewCounter",
+            Field::MylistCounter => "mylistCounter",
+            Field::LikeCounter => "likeCounter",
+            Field::LengthSeconds => "lengthSeconds",
+            Field::ThumbnailUrl => "thumbnailUrl",
+            Field::StartTime => "startTime",
+            Field::LastResBody => "lastResBody",
+            Field::CommentCounter => "commentCounter",
+            Field::LastCommentTime => "lastCommentTime",
+            Field::CategoryTags => "categoryTags",
+            Field::Tags => "tags",
+            Field::Genre => "genre",
+        }
+    }
+}
+
+/// Represents a filter of the results, which is sent as `jsonFilter`.
+///
+/// # Examples
+/// ```
+/// # use niconico::search::*;
+/// # use chrono::DateTime;
+/// let since = DateTime::parse_from_rfc3339("2020-01-01T00:00:00+09:00")?;
+/// let filter = Filter::And(vec![
+///     Filter::at_least(Field::ViewCounter, 10000),
+///     Filter::between(Field::StartTime, since, since + chrono::Duration::days(7)),
+///     Filter::Not(Box::new(Filter::equal(Field::Genre, "ゲーム"))),
+/// ]);
+/// assert_eq!(
+///     filter.to_json(),
+///     serde_json::json!({
+///         "type": "and",
+///         "filters": [
+///             { "type": "range", "field": "viewCounter", "from": 10000, "include_lower": true },
+///             {
+///                 "type": "range", "field": "startTime",
+///                 "from": "2020-01-01T00:00:00+09:00", "include_lower": true,
+///                 "to": "2020-01-08T00:00:00+09:00", "include_upper": true,
+///             },
+///             { "type": "not", "filter": { "type": "equal", "field": "genre", "value": "ゲーム" } },
+///         ]
+///     })
+/// );
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// Matches if the field equals the value.
+    Equal { field: Field, value: FilterValue },
+    /// Matches if the field is in the range. Both ends are inclusive, and `None` means unbounded.
+    Range {
+        field: Field,
+        from: Option<FilterValue>,
+        to: Option<FilterValue>,
+    },
+    /// Matches if all the filters match.
+    And(Vec<Filter>),
+    /// Matches if any of the filters matches.
+    Or(Vec<Filter>),
+    /// Matches if the filter does not match.
+    Not(Box<Filter>),
+}
+impl Filter {
+    /// Creates [`Filter::Equal`].
+    pub fn equal(field: Field, value: impl Into<FilterValue>) -> Filter {
+        Filter::Equal {
+            field,
+            value: value.into(),
+        }
+    }
+    /// Creates [`Filter::Range`] which matches if the field is `from` or more.
+    pub fn at_least(field: Field, from: impl Into<FilterValue>) -> Filter {
+        Filter::Range {
+            field,
+            from: Some(from.into()),
+            to: None,
+        }
+    }
+    /// Creates [`Filter::Range`] which matches if the field is `to` or less.
+    pub fn at_most(field: Field, to: impl Into<FilterValue>) -> Filter {
+        Filter::Range {
+            field,
+            from: None,
+            to: Some(to.into()),
+        }
+    }
+    /// Creates [`Filter::Range`] which matches if the field is between `from` and `to`, inclusive.
+    pub fn between(
+        field: Field,
+        from: impl Into<FilterValue>,
+        to: impl Into<FilterValue>,
+    ) -> Filter {
+        Filter::Range {
+            field,
+            from: Some(from.into()),
+            to: Some(to.into()),
+        }
+    }
+
+    /// Converts the filter to the JSON which the API accepts.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Filter::Equal { field, value } => serde_json::json!({
+                "type": "equal",
+                "field": field.as_str(),
+                "value": value.to_json(),
+            }),
+            Filter::Range { field, from, to } => {
+                let mut json = serde_json::json!({
+                    "type": "range",
+                    "field": field.as_str(),
+                });
+                if let Some(from) = from {
+                    json["from"] = from.to_json();
+                    json["include_lower"] = true.into();
+                }
+                if let Some(to) = to {
+                    json["to"] = to.to_json();
+                    json["include_upper"] = true.into();
+                }
+                json
+            }
+            Filter::And(filters) => serde_json::json!({
+                "type": "and",
+                "filters": filters.iter().map(Filter::to_json).collect::<Vec<_>>(),
+            }),
+            Filter::Or(filters) => serde_json::json!({
+                "type": "or",
+                "filters": filters.iter().map(Filter::to_json).collect::<Vec<_>>(),
+            }),
+            Filter::Not(filter) => serde_json::json!({
+                "type": "not",
+                "filter": filter.to_json(),
+            }),
+        }
+    }
+}
+
+/// Represents a value compared in a [`Filter`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    Integer(i64),
+    String(String),
+    DateTime(DateTime<FixedOffset>),
+}
+impl FilterValue {
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            FilterValue::Integer(value) => (*value).into(),
+            FilterValue::String(value) => value.as_str().into(),
+            FilterValue::DateTime(value) => value.to_rfc3339().into(),
+        }
+    }
+}
+impl From<i64> for FilterValue {
+    fn from(value: i64) -> Self {
+        FilterValue::Integer(value)
+    }
+}
+impl From<&str> for FilterValue {
+    fn from(value: &str) -> Self {
+        FilterValue::String(value.to_owned())
+    }
+}
+impl From<String> for FilterValue {
+    fn from(value: String) -> Self {
+        FilterValue::String(value)
+    }
+}
+impl From<DateTime<FixedOffset>> for FilterValue {
+    fn from(value: DateTime<FixedOffset>) -> Self {
+        FilterValue::DateTime(value)
+    }
+}
@@ -0,0 +1,122 @@
+use super::{hit::SearchHit, SearchQuery, MAX_OFFSET, PAGE_SIZE};
+use crate::*;
+use std::collections::VecDeque;
+
+type FetchSearchHitFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<(VecDeque<SearchHit>, bool, usize)>> + 'a>>;
+
+/// Streams videos found by the search. See also [search::search()](super::search).
+pub struct SearchStream<'a> {
+    session: &'a Session,
+    query: SearchQuery,
+    offset: usize,
+    future: Option<FetchSearchHitFuture<'a>>,
+    buf: VecDeque<SearchHit>,
+    is_finished: bool,
+    len: Option<usize>,
+}
+impl<'a> Stream for SearchStream<'a> {
+    type Item = Result<SearchHit>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        if self.buf.is_empty() && self.future.is_none() && !self.is_finished {
+            self.future = Some(Box::pin(Self::gen_future(
+                self.session,
+                self.query.clone(),
+                self.offset,
+            )));
+        }
+        if let Some(future) = self.future.as_mut() {
+            match future.as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => {
+                    self.future = None;
+                    match result {
+                        Ok((buf, is_finished, len)) => {
+                            self.offset += buf.len();
+                            self.buf = buf;
+                            self.is_finished = is_finished;
+                            self.len = Some(len);
+                        }
+                        Err(err) => return Poll::Ready(Some(Err(err))),
+                    }
+                }
+            }
+        }
+
+        Poll::Ready(Ok(self.buf.pop_front()).transpose())
+    }
+}
+impl<'a> SearchStream<'a> {
+    pub fn new(session: &'a Session, query: SearchQuery) -> SearchStream<'a> {
+        SearchStream {
+            session,
+            query,
+            offset: 0,
+            future: None,
+            buf: VecDeque::new(),
+            is_finished: false,
+            len: None,
+        }
+    }
+
+    /// Returns `(hits, is_finished, total_count)`
+    async fn gen_future(
+        session: &'a Session,
+        query: SearchQuery,
+        offset: usize,
+    ) -> Result<(VecDeque<SearchHit>, bool, usize)> {
+        let url = query.to_url(&session.endpoints().search, offset)?;
+        let json = session
+            .get_json(
+                &url,
+                RequestOptions {
+                    cookie_user_session: false,
+                    ..Default::default()
+                },
+            )
+            .await
+            .context("cannot fetch search results")
+            .context(Error::InvalidResponse)?;
+
+        let status = json_extract!(json, as_u64, ["meta"]["status"]);
+        if status != 200 {
+            let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+            let error_message = json_extract_optional!(json, as_str, ["meta"]["errorMessage"]);
+            bail!(anyhow!(
+                "{} {}: {}",
+                status,
+                error_code,
+                error_message.unwrap_or_default()
+            )
+            .context(Error::InvalidResponse));
+        }
+
+        let mut hits = VecDeque::new();
+        for hit in json_extract!(json, as_array, ["data"]) {
+            hits.push_back(SearchHit::from_json(hit)?);
+        }
+
+        let len = json_extract!(json, as_u64, ["meta"]["totalCount"]) as usize;
+        let next_offset = offset + hits.len();
+        let is_finished = hits.len() < PAGE_SIZE || next_offset >= len || next_offset > MAX_OFFSET;
+
+        Ok((hits, is_finished, len))
+    }
+    /// Fetches the number of the videos which match the query.
+    ///
+    /// The stream may yield fewer videos than this, because the API cannot page beyond a certain offset.
+    pub async fn len(&mut self) -> Result<usize> {
+        // total count is written in all response.
+        // if not received any response yet, do the first request.
+        if self.len.is_none() {
+            let (buf, is_finished, len) =
+                Self::gen_future(self.session, self.query.clone(), self.offset).await?;
+            self.offset += buf.len();
+            self.buf = buf;
+            self.is_finished = is_finished;
+            self.len = Some(len);
+        }
+
+        Ok(self.len.unwrap())
+    }
+}
@@ -0,0 +1,248 @@
+//! Fetches series, which creators organize their videos into.
+
+use crate::*;
+use video::summary::VideoSummary;
+
+/// The number of items fetched at once.
+const PAGE_SIZE: usize = 100;
+
+/// Represents the ID of a series.
+///
+/// # Examples
+/// ```
+/// # use niconico::series::SeriesId;
+/// let id: SeriesId = "https://www.nicovideo.jp/series/12345?ref=pc_watch".parse()?;
+/// assert_eq!(id, SeriesId::new(12345));
+/// assert_eq!(id.to_string(), "12345");
+///
+/// assert!("https://www.nicovideo.jp/mylist/12345".parse::<SeriesId>().is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct SeriesId(u64);
+impl SeriesId {
+    /// Creates an ID from the ID number.
+    pub fn new(id: u64) -> SeriesId {
+        SeriesId(id)
+    }
+    /// Returns the ID number.
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+    /// Gets the url of the series page.
+    pub fn series_url(self) -> String {
+        format!("https://www.nicovideo.jp/series/{}", self.0)
+    }
+    /// Fetches the series with all its items.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use niconico::{*, series::SeriesId};
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let session = Session::new(USER_AGENT, Language::Japanese);
+    /// let series = SeriesId::new(12345).fetch(&session).await?;
+    /// for video in &series.items {
+    ///     println!("{}", video.title);
+    /// }
+    ///
+    /// let first = &series.items[0].id;
+    /// assert!(series.previous(first).is_none());
+    /// println!("next: {:?}", series.next(first).map(|video| &video.title));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch(self, session: &Session) -> Result<Series> {
+        Series::fetch(session, self).await
+    }
+}
+impl std::str::FromStr for SeriesId {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // accepts series urls such as `https://www.nicovideo.jp/series/123`
+        let mut segments = s
+            .split(['?', '#'].as_ref())
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches('/')
+            .rsplit('/');
+        let id = segments.next().unwrap_or_default();
+        let is_valid = matches!(segments.next(), None | Some("series"))
+            && !id.is_empty()
+            && id.bytes().all(|b| b.is_ascii_digit());
+        ensure!(
+            is_valid,
+            anyhow!("`{}` is not a series id", s).context(Error::InvalidId)
+        );
+
+        Ok(SeriesId(id.parse().context(Error::InvalidId)?))
+    }
+}
+impl std::fmt::Display for SeriesId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Represents a series in the list of a user's series.
+#[derive(Debug, Clone)]
+pub struct SeriesSummary {
+    /// The ID of this series.
+    pub id: SeriesId,
+    /// The title of this series.
+    pub title: String,
+    /// The description of this series.
+    pub description: String,
+    /// The URL of the thumbnail of this series.
+    pub thumbnail_url: String,
+    /// The number of the videos in this series.
+    pub item_count: u64,
+    /// Whether this series is listed on the owner's page.
+    pub is_listed: bool,
+}
+impl SeriesSummary {
+    /// Fetches the series of the user. See also [`User::fetch_series_list`].
+    pub async fn fetch_list(session: &Session, user: User) -> Result<Vec<SeriesSummary>> {
+        let mut list = Vec::new();
+        for page in 1.. {
+            let url = format!(
+                "{}/v1/users/{}/series?page={}&pageSize={}",
+                session.endpoints().nvapi,
+                user.nvapi_id(),
+                page,
+                PAGE_SIZE
+            );
+            let json = get_json(session, &url)
+                .await
+                .context("cannot fetch series list")?;
+
+            let items = json_extract!(json, as_array, ["data"]["items"]);
+            for series in items {
+                list.push(SeriesSummary {
+                    id: SeriesId(json_extract!(series, as_u64, ["id"])),
+                    title: json_extract!(series, as_string, ["title"]),
+                    description: json_extract!(series, as_string, ["description"]),
+                    thumbnail_url: json_extract!(series, as_string, ["thumbnailUrl"]),
+                    item_count: json_extract!(series, as_u64, ["itemsCount"]),
+                    is_listed: json_extract!(series, as_bool, ["isListed"]),
+                });
+            }
+
+            let total_count = json_extract!(json, as_u64, ["data"]["totalCount"]) as usize;
+            if items.len() < PAGE_SIZE || list.len() >= total_count {
+                break;
+            }
+        }
+        Ok(list)
+    }
+}
+
+/// Represents a series with its items.
+#[derive(Debug, Clone)]
+pub struct Series {
+    /// The ID of this series.
+    pub id: SeriesId,
+    /// The title of this series.
+    pub title: String,
+    /// The description of this series.
+    pub description: String,
+    /// The URL of the thumbnail of this series.
+    pub thumbnail_url: String,
+    /// The owner of this series. `None` if the owner is a channel.
+    pub owner: Option<User>,
+    /// The videos in this series, in the order the owner has arranged.
+    pub items: Vec<VideoSummary>,
+}
+impl Series {
+    /// Fetches the series. See also [`SeriesId::fetch`].
+    pub async fn fetch(session: &Session, id: SeriesId) -> Result<Series> {
+        let mut series: Option<Series> = None;
+        for page in 1.. {
+            let url = format!(
+                "{}/v2/series/{}?page={}&pageSize={}",
+                session.endpoints().nvapi,
+                id,
+                page,
+                PAGE_SIZE
+            );
+            let json = get_json(session, &url)
+                .await
+                .context("cannot fetch the series")?;
+
+            let series = match &mut series {
+                Some(series) => series,
+                None => {
+                    let detail = &json["data"]["detail"];
+                    let owner = match json_extract!(detail, as_str, ["owner"]["type"]) {
+                        "user" => Some(User::UserId(
+                            json_extract!(detail, as_str, ["owner"]["id"])
+                                .parse()
+                                .context(Error::InvalidResponse)?,
+                        )),
+                        _ => None,
+                    };
+                    series.get_or_insert(Series {
+                        id,
+                        title: json_extract!(detail, as_string, ["title"]),
+                        description: json_extract!(detail, as_string, ["description"]),
+                        thumbnail_url: json_extract!(detail, as_string, ["thumbnailUrl"]),
+                        owner,
+                        items: Vec::new(),
+                    })
+                }
+            };
+
+            let items = json_extract!(json, as_array, ["data"]["items"]);
+            for item in items {
+                series.items.push(VideoSummary::from_json(&item["video"])?);
+            }
+
+            let total_count = json_extract!(json, as_u64, ["data"]["totalCount"]) as usize;
+            if items.len() < PAGE_SIZE || series.items.len() >= total_count {
+                break;
+            }
+        }
+        // the loop runs at least once
+        Ok(series.unwrap())
+    }
+
+    /// Returns the video before the video in this series. `None` if the video is the first or not in this series.
+    pub fn previous(&self, video: &VideoId) -> Option<&VideoSummary> {
+        let index = self.position(video)?;
+        index.checked_sub(1).map(|index| &self.items[index])
+    }
+    /// Returns the video after the video in this series. `None` if the video is the last or not in this series.
+    pub fn next(&self, video: &VideoId) -> Option<&VideoSummary> {
+        let index = self.position(video)?;
+        self.items.get(index + 1)
+    }
+    /// Returns the index of the video in this series.
+    pub fn position(&self, video: &VideoId) -> Option<usize> {
+        self.items.iter().position(|item| &item.id == video)
+    }
+}
+
+async fn get_json(session: &Session, url: &str) -> Result<serde_json::Value> {
+    let json = session
+        .get_json(
+            url,
+            RequestOptions {
+                header_x_frontend_id: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .context(Error::InvalidResponse)?;
+
+    let status = json_extract!(json, as_u64, ["meta"]["status"]);
+    match status {
+        200 => Ok(json),
+        401 => bail!(Error::LoginRequired),
+        403 => bail!(Error::PrivateList),
+        _ => {
+            let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+            bail!(anyhow!("{} {}", status, error_code).context(Error::InvalidResponse))
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use crate::*;
 
 pub async fn login(session: &mut Session, email_or_tel: &str, password: &str) -> Result<()> {
+    let url = format!("{}/login/redirector", session.endpoints().account);
     let response = session
         .post(
-            "https://account.nicovideo.jp/login/redirector",
+            &url,
             RequestOptions {
                 cookie_user_session: false,
                 ..Default::default()
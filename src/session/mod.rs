@@ -11,6 +11,8 @@ pub struct Session {
     cookie_user_session: Option<String>,
     /// The language to include in every request as `Accept-Language`.
     language: Language,
+    /// The base URLs requests are sent to.
+    endpoints: Endpoints,
 }
 impl Session {
     /// Creates a new session. `user_agent` should be the name of the application.
@@ -41,6 +43,7 @@ impl Session {
                 .unwrap(),
             cookie_user_session: None,
             language,
+            endpoints: Endpoints::default(),
         }
     }
     /// Log in to niconico using specified email address or telephone number and password.
@@ -99,6 +102,33 @@ impl Session {
     pub fn is_logged_in(&self) -> bool {
         self.cookie_user_session.is_some()
     }
+    /// Returns the language included in every request as `Accept-Language`.
+    pub fn language(&self) -> Language {
+        self.language
+    }
+    /// Returns the base URLs requests are sent to.
+    pub fn endpoints(&self) -> &Endpoints {
+        &self.endpoints
+    }
+    /// Replaces the base URLs requests are sent to.
+    /// This is mainly useful to point the session at a local server in tests.
+    ///
+    /// # Examples
+    /// ```
+    /// # use niconico::*;
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// let mut session = Session::new(USER_AGENT, Language::Japanese);
+    ///
+    /// let mut endpoints = Endpoints::default();
+    /// endpoints.nvapi = "http://127.0.0.1:8080".to_owned();
+    /// session.set_endpoints(endpoints);
+    ///
+    /// assert_eq!(session.endpoints().nvapi, "http://127.0.0.1:8080");
+    /// assert_eq!(session.endpoints().www, "https://www.nicovideo.jp");
+    /// ```
+    pub fn set_endpoints(&mut self, endpoints: Endpoints) {
+        self.endpoints = endpoints;
+    }
 
     /// Makes a GET request. Includes cookie `user_session` if `include_cookie` is `true`.
     pub(crate) fn get(
@@ -106,22 +136,25 @@ impl Session {
         url: &str,
         options: impl Into<Option<RequestOptions>>,
     ) -> reqwest::RequestBuilder {
-        let options = options.into().unwrap_or_default();
-
-        let mut req = self.client.get(url);
-        if options.cookie_user_session && self.cookie_user_session.is_some() {
-            req = req.header(
-                "Cookie",
-                &format!(
-                    "user_session={}",
-                    self.cookie_user_session.as_ref().unwrap()
-                ),
-            );
-        }
-        if options.header_x_frontend_id {
-            req = req.header("X-Frontend-Id", 6);
-        }
-        req
+        self.request(reqwest::Method::GET, url, options)
+    }
+    /// Gets the response body as text.
+    pub(crate) async fn get_text(
+        &self,
+        url: &str,
+        options: impl Into<Option<RequestOptions>>,
+    ) -> Result<String> {
+        let text = self
+            .get(url, options)
+            .send()
+            .await
+            .with_context(|| format!("cannot fetch from `{}`", url))
+            .context(Error::InvalidResponse)?
+            .text()
+            .await
+            .with_context(|| format!("cannot fetch from `{}`", url))
+            .context(Error::InvalidResponse)?;
+        Ok(text)
     }
     /// Gets html and extracts data from it.
     pub(crate) async fn get_data<T>(
@@ -170,35 +203,126 @@ impl Session {
         &self,
         url: &str,
         options: impl Into<Option<RequestOptions>>,
+    ) -> reqwest::RequestBuilder {
+        self.request(reqwest::Method::POST, url, options)
+    }
+    /// Makes a PUT request. Includes cookie `user_session` if `include_cookie` is `true`.
+    pub(crate) fn put(
+        &self,
+        url: &str,
+        options: impl Into<Option<RequestOptions>>,
+    ) -> reqwest::RequestBuilder {
+        self.request(reqwest::Method::PUT, url, options)
+    }
+    /// Makes a PATCH request. Includes cookie `user_session` if `include_cookie` is `true`.
+    pub(crate) fn patch(
+        &self,
+        url: &str,
+        options: impl Into<Option<RequestOptions>>,
+    ) -> reqwest::RequestBuilder {
+        self.request(reqwest::Method::PATCH, url, options)
+    }
+    /// Makes a DELETE request. Includes cookie `user_session` if `include_cookie` is `true`.
+    pub(crate) fn delete(
+        &self,
+        url: &str,
+        options: impl Into<Option<RequestOptions>>,
+    ) -> reqwest::RequestBuilder {
+        self.request(reqwest::Method::DELETE, url, options)
+    }
+    /// Sends a request made by this session and parses the response as json.
+    pub(crate) async fn send_json(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<serde_json::Value> {
+        let request = request.build().context(Error::InvalidResponse)?;
+        let url = request.url().to_string();
+        let json_str = self
+            .client
+            .execute(request)
+            .await
+            .with_context(|| format!("cannot fetch from `{}`", url))
+            .context(Error::InvalidResponse)?
+            .text()
+            .await
+            .with_context(|| format!("cannot fetch from `{}`", url))
+            .context(Error::InvalidResponse)?;
+        let json = serde_json::from_str(&json_str).context(Error::InvalidResponse)?;
+        Ok(json)
+    }
+    /// Makes a request with the given method.
+    fn request(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+        options: impl Into<Option<RequestOptions>>,
     ) -> reqwest::RequestBuilder {
         let options = options.into().unwrap_or_default();
 
-        let mut req = self.client.post(url);
-        if options.cookie_user_session && self.cookie_user_session.is_some() {
-            req = req.header(
-                "Cookie",
-                &format!(
-                    "user_session={}",
-                    self.cookie_user_session.as_ref().unwrap()
-                ),
-            );
+        let mut req = self.client.request(method, url);
+        if options.cookie_user_session {
+            if let Some(cookie_user_session) = &self.cookie_user_session {
+                req = req.header("Cookie", &format!("user_session={}", cookie_user_session));
+            }
         }
         if options.header_x_frontend_id {
             req = req.header("X-Frontend-Id", 6);
         }
+        if options.header_x_request_with {
+            req = req.header("X-Request-With", "https://www.nicovideo.jp");
+        }
         req
     }
 }
 
+/// The base URLs of the niconico services. Each field has no trailing slash.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Endpoints {
+    /// The base URL of the account service. (`https://account.nicovideo.jp`)
+    pub account: String,
+    /// The base URL of the main site. (`https://www.nicovideo.jp`)
+    pub www: String,
+    /// The base URL of nvapi. (`https://nvapi.nicovideo.jp`)
+    pub nvapi: String,
+    /// The base URL of the public API. (`https://public.api.nicovideo.jp`)
+    pub public_api: String,
+    /// The base URL of the external API. (`https://ext.nicovideo.jp`)
+    pub ext: String,
+    /// The base URL of the flash API, which issues keys for comment servers. (`https://flapi.nicovideo.jp`)
+    pub flapi: String,
+    /// The base URL of the snapshot search API. (`https://snapshot.search.nicovideo.jp`)
+    pub search: String,
+    /// The base URL of Niconico Live. (`https://live.nicovideo.jp`)
+    pub live: String,
+    /// The base URL of Niconico Channel. (`https://ch.nicovideo.jp`)
+    pub ch: String,
+}
+impl Default for Endpoints {
+    fn default() -> Self {
+        Endpoints {
+            account: "https://account.nicovideo.jp".to_owned(),
+            www: "https://www.nicovideo.jp".to_owned(),
+            nvapi: "https://nvapi.nicovideo.jp".to_owned(),
+            public_api: "https://public.api.nicovideo.jp".to_owned(),
+            ext: "https://ext.nicovideo.jp".to_owned(),
+            flapi: "https://flapi.nicovideo.jp".to_owned(),
+            search: "https://snapshot.search.nicovideo.jp".to_owned(),
+            live: "https://live.nicovideo.jp".to_owned(),
+            ch: "https://ch.nicovideo.jp".to_owned(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Language {
     Japanese,
     English,
     Chinese,
 }
-impl Into<reqwest::header::HeaderValue> for Language {
-    fn into(self) -> reqwest::header::HeaderValue {
-        match self {
+impl From<Language> for reqwest::header::HeaderValue {
+    fn from(language: Language) -> Self {
+        match language {
             Language::Japanese => reqwest::header::HeaderValue::from_static("ja"),
             Language::English => reqwest::header::HeaderValue::from_static("en"),
             Language::Chinese => reqwest::header::HeaderValue::from_static("zh"),
@@ -211,12 +335,16 @@ pub(crate) struct RequestOptions {
     pub cookie_user_session: bool,
     /// Whether header `X-Frontend-Id` is include in requests.
     pub header_x_frontend_id: bool,
+    /// Whether header `X-Request-With` is include in requests.
+    /// nvapi requires this header for requests that change something, to prevent CSRF.
+    pub header_x_request_with: bool,
 }
 impl Default for RequestOptions {
     fn default() -> Self {
         RequestOptions {
             cookie_user_session: true,
             header_x_frontend_id: false,
+            header_x_request_with: false,
         }
     }
 }
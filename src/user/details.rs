@@ -55,7 +55,7 @@ impl UserDetails {
             Error::LoginRequired
         );
 
-        let user_page: UserPage = session.get_data(&user.user_page_url(), None).await?;
+        let user_page: UserPage = session.get_data(&user.user_page_url(session), None).await?;
         let data: serde_json::Value = serde_json::from_str(&user_page.js_initial_user_page_data)?;
         let user_data = &data["userDetails"]["userDetails"]["user"];
 
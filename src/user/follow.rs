@@ -0,0 +1,56 @@
+use crate::*;
+
+/// Follows the user. See also [`User::follow`](super::User::follow).
+pub(crate) async fn follow(session: &Session, user: User) -> Result<()> {
+    let request = session.post(&gen_url(session, user)?, request_options());
+    send(session, request)
+        .await
+        .context("cannot follow the user")
+}
+/// Unfollows the user. See also [`User::unfollow`](super::User::unfollow).
+pub(crate) async fn unfollow(session: &Session, user: User) -> Result<()> {
+    let request = session.delete(&gen_url(session, user)?, request_options());
+    send(session, request)
+        .await
+        .context("cannot unfollow the user")
+}
+
+async fn send(session: &Session, request: reqwest::RequestBuilder) -> Result<()> {
+    let json = session.send_json(request).await?;
+
+    let status = json_extract!(json, as_u64, ["meta"]["status"]);
+    match status {
+        200 | 201 => Ok(()),
+        401 => bail!(Error::LoginRequired),
+        _ => {
+            let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+            match error_code {
+                "ALREADY_FOLLOWING" => bail!(Error::AlreadyFollowing),
+                "FOLLOW_LIMIT_EXCEEDED" => bail!(Error::FollowLimitReached),
+                "CANNOT_FOLLOW_MYSELF" => bail!(Error::SelfFollow),
+                _ => bail!(anyhow!("{} {}", status, error_code).context(Error::InvalidResponse)),
+            }
+        }
+    }
+}
+
+fn request_options() -> RequestOptions {
+    RequestOptions {
+        header_x_frontend_id: true,
+        header_x_request_with: true,
+        ..Default::default()
+    }
+}
+
+fn gen_url(session: &Session, user: User) -> Result<String> {
+    ensure!(session.is_logged_in(), Error::LoginRequired);
+    let id = match user {
+        User::LoginUser => bail!(Error::SelfFollow),
+        User::UserId(id) => id,
+    };
+    Ok(format!(
+        "{}/v1/users/me/following/users/{}",
+        session.endpoints().nvapi,
+        id
+    ))
+}
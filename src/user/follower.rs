@@ -0,0 +1,7 @@
+use super::following_user::FollowingUserStream;
+
+/// Streams list of users who follow the user.
+///
+/// This is a [`FollowingUserStream`] which fetches the followers instead of the users being followed,
+/// so it yields [`FollowingUser`](super::following_user::FollowingUser)s as well. See [`User::stream_followers`](crate::User::stream_followers).
+pub type FollowerStream<'a> = FollowingUserStream<'a>;
@@ -50,9 +50,22 @@ type FetchFollowingUserFuture<'a> = Pin<
     Box<dyn Future<Output = Result<(VecDeque<FollowingUser>, bool, Option<String>, usize)>> + 'a>,
 >;
 
-/// Streams list of users who the user is following.
+/// Which list of users is streamed by [`FollowingUserStream`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Direction {
+    /// The users who the user is following.
+    Following,
+    /// The users who follow the user.
+    Followers,
+}
+
+/// Streams list of users who the user is following. See also [`User::stream_following_users`].
+///
+/// The followers of the user are streamed by the same type, as [`FollowerStream`](super::follower::FollowerStream).
 pub struct FollowingUserStream<'a> {
     session: &'a Session,
+    user: User,
+    direction: Direction,
     future: Option<FetchFollowingUserFuture<'a>>,
     buf: VecDeque<FollowingUser>,
     is_finished: bool,
@@ -65,6 +78,8 @@ impl<'a> Stream for FollowingUserStream<'a> {
         if self.buf.is_empty() && self.future.is_none() && !self.is_finished {
             self.future = Some(Box::pin(Self::gen_future(
                 self.session,
+                self.user,
+                self.direction,
                 self.next_cursor.take(),
             )));
         }
@@ -90,9 +105,18 @@ impl<'a> Stream for FollowingUserStream<'a> {
     }
 }
 impl<'a> FollowingUserStream<'a> {
-    pub fn new(session: &Session) -> FollowingUserStream {
+    pub fn new(session: &Session, user: User) -> FollowingUserStream<'_> {
+        FollowingUserStream::with_direction(session, user, Direction::Following)
+    }
+    pub(crate) fn with_direction(
+        session: &Session,
+        user: User,
+        direction: Direction,
+    ) -> FollowingUserStream<'_> {
         FollowingUserStream {
             session,
+            user,
+            direction,
             future: None,
             buf: VecDeque::new(),
             is_finished: false,
@@ -102,9 +126,11 @@ impl<'a> FollowingUserStream<'a> {
     }
     async fn gen_future(
         session: &'a Session,
+        user: User,
+        direction: Direction,
         next_cursor: Option<String>,
     ) -> Result<(VecDeque<FollowingUser>, bool, Option<String>, usize)> {
-        let url = gen_url(next_cursor);
+        let url = gen_url(&session.endpoints().nvapi, user, direction, next_cursor);
         let json = session
             .get_json(
                 &url,
@@ -114,13 +140,17 @@ impl<'a> FollowingUserStream<'a> {
                 },
             )
             .await
-            .context("cannot fetch following user list")
+            .context(match direction {
+                Direction::Following => "cannot fetch following user list",
+                Direction::Followers => "cannot fetch follower list",
+            })
             .context(Error::InvalidResponse)?;
 
         let status = json_extract!(json, as_u64, ["meta"]["status"]);
         match status {
             200 => (),
             401 => bail!(Error::LoginRequired),
+            403 => bail!(Error::PrivateList),
             _ => {
                 let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
                 bail!(anyhow!("{} {}", status, error_code).context(Error::InvalidResponse));
@@ -132,7 +162,10 @@ impl<'a> FollowingUserStream<'a> {
             following_users.push_back(FollowingUser::from_json(user)?);
         }
 
-        let len = json_extract!(json, as_u64, ["data"]["summary"]["followees"]) as usize;
+        let len = match direction {
+            Direction::Following => json_extract!(json, as_u64, ["data"]["summary"]["followees"]),
+            Direction::Followers => json_extract!(json, as_u64, ["data"]["summary"]["followers"]),
+        } as usize;
         let next_cursor = json_extract_optional!(json, as_string, ["data"]["summary"]["cursor"]);
         let is_finished = !json_extract!(json, as_bool, ["data"]["summary"]["hasNext"]);
 
@@ -143,7 +176,8 @@ impl<'a> FollowingUserStream<'a> {
         // length is written in all response.
         // if not received any response yet, do the first request.
         if self.len.is_none() {
-            let (buf, is_finished, next_cursor, len) = Self::gen_future(self.session, None).await?;
+            let (buf, is_finished, next_cursor, len) =
+                Self::gen_future(self.session, self.user, self.direction, None).await?;
             self.buf = buf;
             self.is_finished = is_finished;
             self.next_cursor = next_cursor;
@@ -154,13 +188,18 @@ impl<'a> FollowingUserStream<'a> {
     }
 }
 
-fn gen_url(next_cursor: Option<String>) -> Cow<'static, str> {
+fn gen_url(nvapi: &str, user: User, direction: Direction, next_cursor: Option<String>) -> String {
+    let mut url = format!(
+        "{}/v1/users/{}/{}/users?pageSize=25",
+        nvapi,
+        user.nvapi_id(),
+        match direction {
+            Direction::Following => "following",
+            Direction::Followers => "followed-by",
+        }
+    );
     if let Some(next_cursor) = next_cursor {
-        Cow::Owned(format!(
-            "https://nvapi.nicovideo.jp/v1/users/me/following/users?pageSize=25&cursor={}",
-            next_cursor
-        ))
-    } else {
-        Cow::Borrowed("https://nvapi.nicovideo.jp/v1/users/me/following/users?pageSize=25")
+        url += &format!("&cursor={}", next_cursor);
     }
+    url
 }
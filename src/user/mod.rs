@@ -1,7 +1,11 @@
 use crate::*;
 
 pub mod details;
+mod follow;
+pub mod follower;
 pub mod following_user;
+pub mod uploaded_video;
+pub mod watch_history;
 
 /// Represents a user.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
@@ -12,11 +16,31 @@ pub enum User {
     UserId(u64),
 }
 impl User {
-    /// Gets the url of the user page.
-    pub fn user_page_url(self) -> Cow<'static, str> {
+    /// Gets the url of the user page, on [`Endpoints::www`](crate::Endpoints::www) of the session.
+    ///
+    /// # Examples
+    /// ```
+    /// # use niconico::*;
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// let mut session = Session::new(USER_AGENT, Language::Japanese);
+    /// assert_eq!(User::UserId(1).user_page_url(&session), "https://www.nicovideo.jp/user/1");
+    ///
+    /// let mut endpoints = Endpoints::default();
+    /// endpoints.www = "http://127.0.0.1:8080".to_owned();
+    /// session.set_endpoints(endpoints);
+    /// assert_eq!(User::LoginUser.user_page_url(&session), "http://127.0.0.1:8080/my");
+    /// ```
+    pub fn user_page_url(self, session: &Session) -> String {
+        match self {
+            User::LoginUser => format!("{}/my", session.endpoints().www),
+            User::UserId(id) => format!("{}/user/{}", session.endpoints().www, id),
+        }
+    }
+    /// Gets the segment that identifies this user in nvapi paths. (`me` or the ID number)
+    pub(crate) fn nvapi_id(self) -> Cow<'static, str> {
         match self {
-            User::LoginUser => Cow::Borrowed("https://www.nicovideo.jp/my"),
-            User::UserId(id) => Cow::Owned(format!("https://www.nicovideo.jp/user/{}", id)),
+            User::LoginUser => Cow::Borrowed("me"),
+            User::UserId(id) => Cow::Owned(id.to_string()),
         }
     }
     /// Fetches the details of this user.
@@ -36,7 +60,10 @@ impl User {
         details::UserDetails::fetch(session, self).await
     }
 
-    /// Fetched the list of users the login user is following.
+    /// Fetches the list of users this user is following.
+    ///
+    /// # Errors
+    /// The stream yields `Error::PrivateList` if this user does not publish the list.
     ///
     /// # Examples
     /// ```
@@ -60,7 +87,175 @@ impl User {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn stream_following_users(self, session: &Session) -> following_user::FollowingUserStream {
-        following_user::FollowingUserStream::new(session)
+    pub fn stream_following_users(
+        self,
+        session: &Session,
+    ) -> following_user::FollowingUserStream<'_> {
+        following_user::FollowingUserStream::new(session, self)
+    }
+
+    /// Fetches the list of users who follow this user.
+    ///
+    /// # Errors
+    /// The stream yields `Error::PrivateList` if this user does not publish the list.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use niconico::*;
+    /// use futures::StreamExt;
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let mut session = Session::new(USER_AGENT, Language::Japanese);
+    /// # session.set_cookie_user_session(&std::env::var("NICO_SID")?);
+    ///
+    /// let mut user_2_followers = User::UserId(2).stream_followers(&session);
+    /// println!("{} followers", user_2_followers.len().await?);
+    /// while let Some(user) = user_2_followers.next().await {
+    ///    println!("{:#?}", user);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stream_followers(self, session: &Session) -> follower::FollowerStream<'_> {
+        follower::FollowerStream::with_direction(
+            session,
+            self,
+            following_user::Direction::Followers,
+        )
+    }
+
+    /// Fetches the videos uploaded by this user.
+    ///
+    /// # Errors
+    /// The stream yields `Error::PrivateList` if this user does not publish the list.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use niconico::{*, user::uploaded_video::*};
+    /// use futures::StreamExt;
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let session = Session::new(USER_AGENT, Language::Japanese);
+    /// let sort = UploadedVideoSort {
+    ///     key: UploadedVideoSortKey::ViewCount,
+    ///     ..Default::default()
+    /// };
+    /// let mut videos = User::UserId(2).stream_videos(&session, sort);
+    /// println!("{} videos", videos.len().await?);
+    /// while let Some(video) = videos.next().await {
+    ///     println!("{:#?}", video?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stream_videos(
+        self,
+        session: &Session,
+        sort: uploaded_video::UploadedVideoSort,
+    ) -> uploaded_video::UploadedVideoStream<'_> {
+        uploaded_video::UploadedVideoStream::new(session, self, sort)
+    }
+
+    /// Fetches the series this user has created.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use niconico::*;
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let session = Session::new(USER_AGENT, Language::Japanese);
+    /// for series in User::UserId(2).fetch_series_list(&session).await? {
+    ///     println!("{}: {} videos", series.title, series.item_count);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_series_list(self, session: &Session) -> Result<Vec<series::SeriesSummary>> {
+        series::SeriesSummary::fetch_list(session, self).await
+    }
+
+    /// Fetches the watch history of this user, most recently watched first.
+    /// To delete entries, see [`watch_history::delete`] and [`watch_history::clear`].
+    ///
+    /// # Errors
+    /// The stream yields
+    /// - `Error::LoginRequired` if the session is not logged in.
+    /// - `Error::PrivateList` if this user is not [`User::LoginUser`], because watch histories are never public.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use niconico::*;
+    /// use futures::StreamExt;
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let mut session = Session::new(USER_AGENT, Language::Japanese);
+    /// # session.set_cookie_user_session(&std::env::var("NICO_SID")?);
+    /// let mut history = User::LoginUser.stream_watch_history(&session);
+    /// while let Some(item) = history.next().await {
+    ///     let item = item?;
+    ///     println!("{} ({} times, last at {})", item.video.title, item.watch_count, item.last_watched_at);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stream_watch_history(self, session: &Session) -> watch_history::WatchHistoryStream<'_> {
+        watch_history::WatchHistoryStream::new(session, self)
+    }
+
+    /// Fetches the mylists of this user. Only public mylists are included unless this user is the login user.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use niconico::*;
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let mut session = Session::new(USER_AGENT, Language::Japanese);
+    /// # session.set_cookie_user_session(&std::env::var("NICO_SID")?);
+    /// for mylist in User::LoginUser.fetch_mylists(&session).await? {
+    ///     println!("{}: {} items", mylist.name, mylist.item_count);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_mylists(self, session: &Session) -> Result<Vec<mylist::Mylist>> {
+        mylist::Mylist::fetch_list(session, self).await
+    }
+
+    /// Makes the login user follow this user.
+    ///
+    /// # Errors
+    /// - `Error::LoginRequired` if the session is not logged in.
+    /// - `Error::AlreadyFollowing` if the login user is already following this user.
+    /// - `Error::FollowLimitReached` if the login user cannot follow any more users.
+    /// - `Error::SelfFollow` if this user is the login user.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use niconico::*;
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let mut session = Session::new(USER_AGENT, Language::Japanese);
+    /// # session.set_cookie_user_session(&std::env::var("NICO_SID")?);
+    /// User::UserId(2).follow(&session).await?;
+    /// User::UserId(2).unfollow(&session).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn follow(self, session: &Session) -> Result<()> {
+        follow::follow(session, self).await
+    }
+    /// Makes the login user unfollow this user. See [`follow`](User::follow) for examples.
+    ///
+    /// # Errors
+    /// - `Error::LoginRequired` if the session is not logged in.
+    /// - `Error::SelfFollow` if this user is the login user.
+    pub async fn unfollow(self, session: &Session) -> Result<()> {
+        follow::unfollow(session, self).await
     }
 }
@@ -0,0 +1,182 @@
+use crate::*;
+use std::collections::VecDeque;
+use video::summary::VideoSummary;
+
+/// Represents an order of the videos uploaded by a user.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct UploadedVideoSort {
+    /// What the videos are sorted by.
+    pub key: UploadedVideoSortKey,
+    /// Whether the videos are sorted in ascending or descending order.
+    pub order: SortOrder,
+}
+impl Default for UploadedVideoSort {
+    /// Newest uploaded first.
+    fn default() -> Self {
+        UploadedVideoSort {
+            key: UploadedVideoSortKey::RegisteredAt,
+            order: SortOrder::Descending,
+        }
+    }
+}
+/// Represents what the videos uploaded by a user are sorted by.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum UploadedVideoSortKey {
+    /// The date when the video was uploaded.
+    RegisteredAt,
+    /// The number of views.
+    ViewCount,
+    /// The date when the last comment was posted.
+    LastCommentedAt,
+    /// The number of comments.
+    CommentCount,
+    /// The number of likes.
+    LikeCount,
+    /// The number of mylists the video is registered to.
+    MylistCount,
+    /// The length of the video.
+    Duration,
+}
+impl UploadedVideoSortKey {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            UploadedVideoSortKey::RegisteredAt => "registeredAt",
+            UploadedVideoSortKey::ViewCount => "viewCount",
+            UploadedVideoSortKey::LastCommentedAt => "lastCommentTime",
+            UploadedVideoSortKey::CommentCount => "commentCount",
+            UploadedVideoSortKey::LikeCount => "likeCount",
+            UploadedVideoSortKey::MylistCount => "mylistCount",
+            UploadedVideoSortKey::Duration => "duration",
+        }
+    }
+}
+
+const PAGE_SIZE: usize = 100;
+
+type FetchUploadedVideoFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<(VecDeque<VideoSummary>, bool, usize)>> + 'a>>;
+
+/// Streams videos uploaded by a user.
+pub struct UploadedVideoStream<'a> {
+    session: &'a Session,
+    user: User,
+    sort: UploadedVideoSort,
+    future: Option<FetchUploadedVideoFuture<'a>>,
+    buf: VecDeque<VideoSummary>,
+    is_finished: bool,
+    next_page: u32,
+    len: Option<usize>,
+}
+impl<'a> Stream for UploadedVideoStream<'a> {
+    type Item = Result<VideoSummary>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        if self.buf.is_empty() && self.future.is_none() && !self.is_finished {
+            self.future = Some(Box::pin(Self::gen_future(
+                self.session,
+                self.user,
+                self.sort,
+                self.next_page,
+            )));
+        }
+        if let Some(future) = self.future.as_mut() {
+            match future.as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => {
+                    self.future = None;
+                    match result {
+                        Ok((buf, is_finished, len)) => {
+                            self.buf = buf;
+                            self.is_finished = is_finished;
+                            self.next_page += 1;
+                            self.len = Some(len);
+                        }
+                        Err(err) => return Poll::Ready(Some(Err(err))),
+                    }
+                }
+            }
+        }
+
+        Poll::Ready(Ok(self.buf.pop_front()).transpose())
+    }
+}
+impl<'a> UploadedVideoStream<'a> {
+    pub fn new(session: &Session, user: User, sort: UploadedVideoSort) -> UploadedVideoStream<'_> {
+        UploadedVideoStream {
+            session,
+            user,
+            sort,
+            future: None,
+            buf: VecDeque::new(),
+            is_finished: false,
+            next_page: 1,
+            len: None,
+        }
+    }
+    async fn gen_future(
+        session: &'a Session,
+        user: User,
+        sort: UploadedVideoSort,
+        page: u32,
+    ) -> Result<(VecDeque<VideoSummary>, bool, usize)> {
+        let url = gen_url(&session.endpoints().nvapi, user, sort, page);
+        let json = session
+            .get_json(
+                &url,
+                RequestOptions {
+                    header_x_frontend_id: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .context("cannot fetch uploaded videos")
+            .context(Error::InvalidResponse)?;
+
+        let status = json_extract!(json, as_u64, ["meta"]["status"]);
+        match status {
+            200 => (),
+            401 => bail!(Error::LoginRequired),
+            403 => bail!(Error::PrivateList),
+            _ => {
+                let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+                bail!(anyhow!("{} {}", status, error_code).context(Error::InvalidResponse));
+            }
+        }
+
+        let mut items = VecDeque::new();
+        for item in json_extract!(json, as_array, ["data"]["items"]) {
+            items.push_back(VideoSummary::from_json(&item["essential"])?);
+        }
+
+        let len = json_extract!(json, as_u64, ["data"]["totalCount"]) as usize;
+        let is_finished = items.len() < PAGE_SIZE || page as usize * PAGE_SIZE >= len;
+
+        Ok((items, is_finished, len))
+    }
+    /// Fetches the number of the videos.
+    pub async fn len(&mut self) -> Result<usize> {
+        // length is written in all response.
+        // if not received any response yet, do the first request.
+        if self.len.is_none() {
+            let (buf, is_finished, len) =
+                Self::gen_future(self.session, self.user, self.sort, self.next_page).await?;
+            self.buf = buf;
+            self.is_finished = is_finished;
+            self.next_page += 1;
+            self.len = Some(len);
+        }
+
+        Ok(self.len.unwrap())
+    }
+}
+
+fn gen_url(nvapi: &str, user: User, sort: UploadedVideoSort, page: u32) -> String {
+    format!(
+        "{}/v3/users/{}/videos?pageSize={}&page={}&sortKey={}&sortOrder={}",
+        nvapi,
+        user.nvapi_id(),
+        PAGE_SIZE,
+        page,
+        sort.key.as_str(),
+        sort.order.as_str()
+    )
+}
@@ -0,0 +1,217 @@
+use crate::*;
+use std::collections::VecDeque;
+use video::summary::VideoSummary;
+
+/// Represents a video in the watch history.
+#[derive(Debug, Clone)]
+pub struct WatchHistoryItem {
+    /// The watched video.
+    pub video: VideoSummary,
+    /// The date when the video was watched last.
+    pub last_watched_at: DateTime<FixedOffset>,
+    /// How many times the video has been watched.
+    pub watch_count: u64,
+}
+impl WatchHistoryItem {
+    pub(crate) fn from_json(json: &serde_json::Value) -> Result<WatchHistoryItem> {
+        Ok(WatchHistoryItem {
+            video: VideoSummary::from_json(&json["video"])?,
+            last_watched_at: DateTime::<FixedOffset>::parse_from_rfc3339(json_extract!(
+                json,
+                as_str,
+                ["lastViewedAt"]
+            ))
+            .context(Error::InvalidResponse)?,
+            watch_count: json_extract!(json, as_u64, ["views"]),
+        })
+    }
+}
+
+/// Deletes the video from the watch history of the login user.
+///
+/// # Errors
+/// `Error::LoginRequired` if the session is not logged in.
+///
+/// # Examples
+/// ```no_run
+/// # use niconico::{*, user::watch_history};
+/// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// # let mut session = Session::new(USER_AGENT, Language::Japanese);
+/// # session.set_cookie_user_session(&std::env::var("NICO_SID")?);
+/// watch_history::delete(&session, &"sm9".parse()?).await?;
+/// watch_history::clear(&session).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn delete(session: &Session, video: &VideoId) -> Result<()> {
+    let request = session
+        .delete(&gen_delete_url(session)?, request_options())
+        .query(&[("target", video.as_str())]);
+    send(session, request)
+        .await
+        .context("cannot delete the video from the watch history")
+}
+/// Deletes the whole watch history of the login user. See [`delete`] for examples.
+///
+/// # Errors
+/// `Error::LoginRequired` if the session is not logged in.
+pub async fn clear(session: &Session) -> Result<()> {
+    let request = session.delete(&gen_delete_url(session)?, request_options());
+    send(session, request)
+        .await
+        .context("cannot clear the watch history")
+}
+
+async fn send(session: &Session, request: reqwest::RequestBuilder) -> Result<()> {
+    let json = session.send_json(request).await?;
+
+    let status = json_extract!(json, as_u64, ["meta"]["status"]);
+    match status {
+        200 => Ok(()),
+        401 => bail!(Error::LoginRequired),
+        _ => {
+            let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+            bail!(anyhow!("{} {}", status, error_code).context(Error::InvalidResponse))
+        }
+    }
+}
+
+fn request_options() -> RequestOptions {
+    RequestOptions {
+        header_x_frontend_id: true,
+        header_x_request_with: true,
+        ..Default::default()
+    }
+}
+
+fn gen_delete_url(session: &Session) -> Result<String> {
+    ensure!(session.is_logged_in(), Error::LoginRequired);
+    Ok(format!(
+        "{}/v1/users/me/watch/history",
+        session.endpoints().nvapi
+    ))
+}
+
+const PAGE_SIZE: usize = 100;
+
+type FetchWatchHistoryFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<(VecDeque<WatchHistoryItem>, bool, usize)>> + 'a>>;
+
+/// Streams the watch history of the user.
+pub struct WatchHistoryStream<'a> {
+    session: &'a Session,
+    user: User,
+    future: Option<FetchWatchHistoryFuture<'a>>,
+    buf: VecDeque<WatchHistoryItem>,
+    is_finished: bool,
+    next_page: u32,
+    len: Option<usize>,
+}
+impl<'a> Stream for WatchHistoryStream<'a> {
+    type Item = Result<WatchHistoryItem>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        if self.buf.is_empty() && self.future.is_none() && !self.is_finished {
+            self.future = Some(Box::pin(Self::gen_future(
+                self.session,
+                self.user,
+                self.next_page,
+            )));
+        }
+        if let Some(future) = self.future.as_mut() {
+            match future.as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => {
+                    self.future = None;
+                    match result {
+                        Ok((buf, is_finished, len)) => {
+                            self.buf = buf;
+                            self.is_finished = is_finished;
+                            self.next_page += 1;
+                            self.len = Some(len);
+                        }
+                        Err(err) => return Poll::Ready(Some(Err(err))),
+                    }
+                }
+            }
+        }
+
+        Poll::Ready(Ok(self.buf.pop_front()).transpose())
+    }
+}
+impl<'a> WatchHistoryStream<'a> {
+    pub fn new(session: &Session, user: User) -> WatchHistoryStream<'_> {
+        WatchHistoryStream {
+            session,
+            user,
+            future: None,
+            buf: VecDeque::new(),
+            is_finished: false,
+            next_page: 1,
+            len: None,
+        }
+    }
+    async fn gen_future(
+        session: &'a Session,
+        user: User,
+        page: u32,
+    ) -> Result<(VecDeque<WatchHistoryItem>, bool, usize)> {
+        // watch histories of other users are never public
+        ensure!(user == User::LoginUser, Error::PrivateList);
+        ensure!(session.is_logged_in(), Error::LoginRequired);
+
+        let url = format!(
+            "{}/v1/users/me/watch/history?page={}&pageSize={}",
+            session.endpoints().nvapi,
+            page,
+            PAGE_SIZE
+        );
+        let json = session
+            .get_json(
+                &url,
+                RequestOptions {
+                    header_x_frontend_id: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .context("cannot fetch watch history")
+            .context(Error::InvalidResponse)?;
+
+        let status = json_extract!(json, as_u64, ["meta"]["status"]);
+        match status {
+            200 => (),
+            401 => bail!(Error::LoginRequired),
+            _ => {
+                let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+                bail!(anyhow!("{} {}", status, error_code).context(Error::InvalidResponse));
+            }
+        }
+
+        let mut items = VecDeque::new();
+        for item in json_extract!(json, as_array, ["data"]["items"]) {
+            items.push_back(WatchHistoryItem::from_json(item)?);
+        }
+
+        let len = json_extract!(json, as_u64, ["data"]["totalCount"]) as usize;
+        let is_finished = items.len() < PAGE_SIZE || page as usize * PAGE_SIZE >= len;
+
+        Ok((items, is_finished, len))
+    }
+    /// Fetches the number of the videos in the watch history.
+    pub async fn len(&mut self) -> Result<usize> {
+        // length is written in all response.
+        // if not received any response yet, do the first request.
+        if self.len.is_none() {
+            let (buf, is_finished, len) =
+                Self::gen_future(self.session, self.user, self.next_page).await?;
+            self.buf = buf;
+            self.is_finished = is_finished;
+            self.next_page += 1;
+            self.len = Some(len);
+        }
+
+        Ok(self.len.unwrap())
+    }
+}
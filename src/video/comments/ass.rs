@@ -0,0 +1,331 @@
+use super::{
+    commands::{CommentColor, CommentPosition, CommentSize},
+    Comment,
+};
+use std::{fmt::Write, time::Duration};
+
+/// Options for [`to_ass`].
+#[derive(Debug, Clone)]
+pub struct AssOptions {
+    /// The width of the video in pixels.
+    pub width: u32,
+    /// The height of the video in pixels.
+    pub height: u32,
+    /// The number of medium comments that fit in the height.
+    pub lanes: u32,
+    /// The name of the font.
+    pub font_name: String,
+    /// How long scrolling comments take to cross the screen.
+    pub scroll_duration: Duration,
+    /// How long top and bottom comments are shown.
+    pub fixed_duration: Duration,
+}
+impl Default for AssOptions {
+    fn default() -> Self {
+        AssOptions {
+            width: 1920,
+            height: 1080,
+            lanes: 11,
+            font_name: "MS PGothic".to_owned(),
+            scroll_duration: Duration::from_secs(4),
+            fixed_duration: Duration::from_secs(3),
+        }
+    }
+}
+
+/// Converts comments to Advanced SubStation Alpha (ASS) subtitles.
+///
+/// Scrolling comments are laid out so that they do not overtake each other,
+/// and top and bottom comments are stacked while the lanes are occupied.
+/// When all lanes are occupied, the lane that becomes free the earliest is used.
+/// Comments with `invisible` or a zero duration such as `@0` are skipped.
+///
+/// # Examples
+/// ```
+/// # use niconico::video::comments::{ass::{self, AssOptions}, Comment};
+/// # use chrono::{DateTime, FixedOffset};
+/// # fn comment(no: u64, vpos: i64, body: &str, commands: &str) -> Comment {
+/// #     Comment {
+/// #         thread: 1, fork: 0, no, vpos, body: body.to_owned(), commands: commands.to_owned(),
+/// #         user_id: None, posted_at: DateTime::<FixedOffset>::parse_from_rfc3339("2007-03-06T00:33:00+09:00").unwrap(),
+/// #         nicoru_count: 0, score: 0, is_premium: false, is_anonymous: false,
+/// #     }
+/// # }
+/// let comments = vec![
+///     comment(1, 100, "first", ""),
+///     comment(2, 100, "second", "red"),
+///     comment(3, 200, "top", "ue big"),
+///     comment(4, 300, "hidden", "invisible"),
+/// ];
+/// let ass = ass::to_ass(&comments, &AssOptions::default());
+///
+/// let events: Vec<&str> = ass.lines().filter(|l| l.starts_with("Dialogue:")).collect();
+/// assert_eq!(events.len(), 3);
+/// // the two comments at the same time are laid out in different lanes
+/// assert!(events[0].contains(r"\move(1920,0,"));
+/// assert!(events[1].contains(r"\move(1920,98,"));
+/// assert!(events[1].contains(r"\c&H0000FF&"));
+/// assert!(events[2].contains(r"\an8\pos(960,0)"));
+/// ```
+///
+/// When there are more comments than lanes, they overlap in the lane that becomes free the earliest.
+/// Bottom comments are stacked from the bottom, and the characters used by ASS tags are escaped.
+/// ```
+/// # use niconico::video::comments::{ass::{self, AssOptions}, Comment};
+/// # use chrono::{DateTime, FixedOffset};
+/// # fn comment(no: u64, vpos: i64, body: &str, commands: &str) -> Comment {
+/// #     Comment {
+/// #         thread: 1, fork: 0, no, vpos, body: body.to_owned(), commands: commands.to_owned(),
+/// #         user_id: None, posted_at: DateTime::<FixedOffset>::parse_from_rfc3339("2007-03-06T00:33:00+09:00").unwrap(),
+/// #         nicoru_count: 0, score: 0, is_premium: false, is_anonymous: false,
+/// #     }
+/// # }
+/// let comments = vec![
+///     comment(1, 0, "a", ""),
+///     comment(2, 0, "b", ""),
+///     comment(3, 0, "c", ""),
+///     comment(4, 0, "bottom", "shita"),
+///     comment(5, 0, "above", "shita"),
+///     comment(6, 0, "gone", "@0"),
+///     comment(7, 0, r"{\b1}", ""),
+/// ];
+/// let options = AssOptions {
+///     lanes: 2,
+///     ..Default::default()
+/// };
+/// let ass = ass::to_ass(&comments, &options);
+///
+/// let events: Vec<&str> = ass.lines().filter(|l| l.starts_with("Dialogue:")).collect();
+/// assert_eq!(events.len(), 6);
+/// assert!(events[0].contains(r"\move(1920,0,"));
+/// assert!(events[1].contains(r"\move(1920,540,"));
+/// // no lane is free, so the third comment overlaps the first
+/// assert!(events[2].contains(r"\move(1920,0,"));
+/// assert!(events[3].contains(r"\an8\pos(960,540)"));
+/// assert!(events[4].contains(r"\an8\pos(960,0)"));
+/// assert!(events[5].ends_with("}｛＼b1｝"));
+/// ```
+pub fn to_ass(comments: &[Comment], options: &AssOptions) -> String {
+    let mut layout = Layout::new(options);
+
+    let mut comments: Vec<&Comment> = comments.iter().collect();
+    comments.sort_by_key(|comment| (comment.vpos, comment.no));
+
+    let mut events = String::new();
+    for comment in comments {
+        if let Some(event) = layout.place(comment) {
+            events += &event;
+            events += "\n";
+        }
+    }
+
+    format!(
+        "[Script Info]\n\
+         ScriptType: v4.00+\n\
+         PlayResX: {width}\n\
+         PlayResY: {height}\n\
+         WrapStyle: 2\n\
+         ScaledBorderAndShadow: yes\n\
+         \n\
+         [V4+ Styles]\n\
+         Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, \
+         Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, \
+         Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+         Style: Default,{font},{font_size},&H00FFFFFF,&H00FFFFFF,&H00000000,&H00000000,\
+         0,0,0,0,100,100,0,0,1,2,0,7,0,0,0,1\n\
+         \n\
+         [Events]\n\
+         Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n\
+         {events}",
+        width = options.width,
+        height = options.height,
+        font = options.font_name,
+        font_size = layout.lane_height.round(),
+        events = events,
+    )
+}
+
+/// The times when a lane becomes free, in seconds.
+#[derive(Debug, Clone, Copy, Default)]
+struct Lane {
+    /// When the tail of the last scrolling comment enters the screen.
+    scroll_tail_entered: f64,
+    /// When the last scrolling comment leaves the screen.
+    scroll_end: f64,
+    /// When the last top comment disappears.
+    top_end: f64,
+    /// When the last bottom comment disappears. Bottom lanes are counted from the bottom.
+    bottom_end: f64,
+}
+
+struct Layout<'a> {
+    options: &'a AssOptions,
+    lane_height: f64,
+    lanes: Vec<Lane>,
+}
+impl<'a> Layout<'a> {
+    fn new(options: &'a AssOptions) -> Layout<'a> {
+        let lane_count = options.lanes.max(1);
+        Layout {
+            options,
+            lane_height: f64::from(options.height) / f64::from(lane_count),
+            lanes: vec![Lane::default(); lane_count as usize],
+        }
+    }
+
+    /// Decides the position of the comment and returns a `Dialogue` line.
+    fn place(&mut self, comment: &Comment) -> Option<String> {
+        let commands = comment.parse_commands();
+        if commands.is_invisible || comment.body.trim().is_empty() {
+            return None;
+        }
+
+        let scale = match commands.size {
+            Some(CommentSize::Big) => 1.5,
+            Some(CommentSize::Small) => 0.6,
+            Some(CommentSize::Medium) | None => 1.0,
+        };
+        let font_size = self.lane_height * scale;
+        let lines: Vec<&str> = comment.body.lines().collect();
+        let text_width = lines
+            .iter()
+            .map(|line| line.chars().map(char_width).sum::<f64>())
+            .fold(0.0, f64::max)
+            * font_size;
+        let occupied = ((lines.len() as f64 * scale).ceil() as usize)
+            .max(1)
+            .min(self.lanes.len());
+
+        let width = f64::from(self.options.width);
+        let start = comment.vpos.max(0) as f64 / 100.0;
+        let position = commands.position.unwrap_or(CommentPosition::Naka);
+        let duration = match position {
+            CommentPosition::Naka => commands.duration.unwrap_or(self.options.scroll_duration),
+            CommentPosition::Ue | CommentPosition::Shita => {
+                commands.duration.unwrap_or(self.options.fixed_duration)
+            }
+        }
+        .as_secs_f64();
+        // the comment is never shown, and its speed cannot be decided
+        if duration <= 0.0 {
+            return None;
+        }
+
+        let mut tags = String::new();
+        let end = match position {
+            CommentPosition::Naka => {
+                let speed = (width + text_width) / duration;
+                let tail_entered = start + text_width / speed;
+                let head_left = start + width / speed;
+                let lane = self.find_lanes(occupied, |lane| {
+                    (lane.scroll_tail_entered - start).max(lane.scroll_end - head_left)
+                });
+                for lane in &mut self.lanes[lane..lane + occupied] {
+                    lane.scroll_tail_entered = tail_entered;
+                    lane.scroll_end = start + duration;
+                }
+                let y = (lane as f64 * self.lane_height).round();
+                let _ = write!(
+                    tags,
+                    r"\move({},{},{},{})",
+                    width.round(),
+                    y,
+                    (-text_width).round(),
+                    y
+                );
+                start + duration
+            }
+            CommentPosition::Ue | CommentPosition::Shita => {
+                let is_top = position == CommentPosition::Ue;
+                let lane = self.find_lanes(occupied, |lane| {
+                    if is_top {
+                        lane.top_end - start
+                    } else {
+                        lane.bottom_end - start
+                    }
+                });
+                for lane in &mut self.lanes[lane..lane + occupied] {
+                    if is_top {
+                        lane.top_end = start + duration;
+                    } else {
+                        lane.bottom_end = start + duration;
+                    }
+                }
+                let y = if is_top {
+                    lane as f64 * self.lane_height
+                } else {
+                    f64::from(self.options.height) - (lane + occupied) as f64 * self.lane_height
+                };
+                let _ = write!(tags, r"\an8\pos({},{})", (width / 2.0).round(), y.round());
+                start + duration
+            }
+        };
+
+        if scale != 1.0 {
+            let _ = write!(tags, r"\fs{}", font_size.round());
+        }
+        if let Some(color) = commands.color {
+            if color != CommentColor::White {
+                let (r, g, b) = color.rgb();
+                let _ = write!(tags, r"\c&H{:02X}{:02X}{:02X}&", b, g, r);
+            }
+            if color == CommentColor::Black {
+                tags += r"\3c&HFFFFFF&";
+            }
+        }
+
+        Some(format!(
+            "Dialogue: 0,{},{},Default,,0,0,0,,{{{}}}{}",
+            format_time(start),
+            format_time(end),
+            tags,
+            escape(&comment.body)
+        ))
+    }
+
+    /// Finds the first run of `occupied` lanes which are all free, that is, `blocked_for` is not positive.
+    /// If there is no such run, returns the run that becomes free the earliest.
+    fn find_lanes(&self, occupied: usize, blocked_for: impl Fn(&Lane) -> f64) -> usize {
+        let mut best = (0, f64::INFINITY);
+        for first in 0..=self.lanes.len() - occupied {
+            let blocked = self.lanes[first..first + occupied]
+                .iter()
+                .map(&blocked_for)
+                .fold(f64::NEG_INFINITY, f64::max);
+            if blocked <= 0.0 {
+                return first;
+            }
+            if blocked < best.1 {
+                best = (first, blocked);
+            }
+        }
+        best.0
+    }
+}
+
+/// Estimates the width of the character relative to the font size.
+fn char_width(c: char) -> f64 {
+    match c {
+        '\u{0}'..='\u{ff}' | '\u{ff61}'..='\u{ff9f}' => 0.5,
+        _ => 1.0,
+    }
+}
+/// Formats seconds as `H:MM:SS.cc`.
+fn format_time(secs: f64) -> String {
+    let centisecs = (secs * 100.0).round() as u64;
+    format!(
+        "{}:{:02}:{:02}.{:02}",
+        centisecs / 360000,
+        centisecs / 6000 % 60,
+        centisecs / 100 % 60,
+        centisecs % 100
+    )
+}
+/// Replaces characters which have special meanings in ASS with their full-width forms.
+fn escape(text: &str) -> String {
+    text.replace('\\', "＼")
+        .replace('{', "｛")
+        .replace('}', "｝")
+        .replace("\r\n", "\n")
+        .replace('\n', "\\N")
+}
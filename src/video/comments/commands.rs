@@ -0,0 +1,342 @@
+use std::{fmt, time::Duration};
+
+/// Represents the parsed commands (`mail`) of a comment.
+///
+/// Tokens that are not recognized are kept in `unknown` and written back by `to_string()`.
+///
+/// # Examples
+/// ```
+/// # use niconico::video::comments::commands::*;
+/// let commands = CommentCommands::parse("184 ue big #ff8000 mincho @3 foo");
+/// assert!(commands.is_anonymous);
+/// assert_eq!(commands.position, Some(CommentPosition::Ue));
+/// assert_eq!(commands.size, Some(CommentSize::Big));
+/// assert_eq!(commands.color, Some(CommentColor::Rgb(0xff, 0x80, 0x00)));
+/// assert_eq!(commands.font, Some(CommentFont::Mincho));
+/// assert_eq!(commands.duration, Some(std::time::Duration::from_secs(3)));
+/// assert_eq!(commands.unknown, vec!["foo".to_owned()]);
+///
+/// assert_eq!(CommentCommands::parse(&commands.to_string()), commands);
+///
+/// // a duration which cannot be represented is kept as an unknown token
+/// let commands = CommentCommands::parse("@1e300");
+/// assert_eq!(commands.duration, None);
+/// assert_eq!(commands.unknown, vec!["@1e300".to_owned()]);
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct CommentCommands {
+    /// The position of the comment. (`ue`, `naka`, `shita`)
+    pub position: Option<CommentPosition>,
+    /// The size of the comment. (`big`, `medium`, `small`)
+    pub size: Option<CommentSize>,
+    /// The color of the comment.
+    pub color: Option<CommentColor>,
+    /// The font of the comment. (`defont`, `mincho`, `gothic`)
+    pub font: Option<CommentFont>,
+    /// Whether the comment is posted anonymously. (`184`)
+    pub is_anonymous: bool,
+    /// How long the comment is shown. (`@5`)
+    pub duration: Option<Duration>,
+    /// Whether the comment is laid out over the full width of the player. (`full`)
+    pub is_full: bool,
+    /// Whether the comment is not shrunk even if it has many lines. (`ender`)
+    pub is_ender: bool,
+    /// Whether the comment is shown above the other comments. (`patissier`)
+    pub is_patissier: bool,
+    /// Whether the comment is not shown. (`invisible`)
+    pub is_invisible: bool,
+    /// The tokens which are not recognized, in the original order.
+    pub unknown: Vec<String>,
+}
+impl CommentCommands {
+    /// Parses space-separated commands. This never fails; unknown tokens are kept in `unknown`.
+    pub fn parse(commands: &str) -> CommentCommands {
+        let mut parsed = CommentCommands::default();
+        for token in commands.split_whitespace() {
+            match token {
+                "184" => parsed.is_anonymous = true,
+                "full" => parsed.is_full = true,
+                "ender" => parsed.is_ender = true,
+                "patissier" => parsed.is_patissier = true,
+                "invisible" => parsed.is_invisible = true,
+                token => {
+                    if let Some(position) = CommentPosition::from_token(token) {
+                        parsed.position = Some(position);
+                    } else if let Some(size) = CommentSize::from_token(token) {
+                        parsed.size = Some(size);
+                    } else if let Some(color) = CommentColor::from_token(token) {
+                        parsed.color = Some(color);
+                    } else if let Some(font) = CommentFont::from_token(token) {
+                        parsed.font = Some(font);
+                    } else if let Some(duration) = parse_duration(token) {
+                        parsed.duration = Some(duration);
+                    } else {
+                        parsed.unknown.push(token.to_owned());
+                    }
+                }
+            }
+        }
+        parsed
+    }
+}
+impl std::str::FromStr for CommentCommands {
+    type Err = std::convert::Infallible;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(CommentCommands::parse(s))
+    }
+}
+impl fmt::Display for CommentCommands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut tokens: Vec<String> = Vec::new();
+        if self.is_anonymous {
+            tokens.push("184".to_owned());
+        }
+        if let Some(position) = self.position {
+            tokens.push(position.token().to_owned());
+        }
+        if let Some(size) = self.size {
+            tokens.push(size.token().to_owned());
+        }
+        if let Some(color) = self.color {
+            tokens.push(color.token());
+        }
+        if let Some(font) = self.font {
+            tokens.push(font.token().to_owned());
+        }
+        if let Some(duration) = self.duration {
+            tokens.push(format!("@{}", duration.as_secs_f64()));
+        }
+        let flags = [
+            (self.is_full, "full"),
+            (self.is_ender, "ender"),
+            (self.is_patissier, "patissier"),
+            (self.is_invisible, "invisible"),
+        ];
+        for (_, flag) in flags.iter().filter(|(is_set, _)| *is_set) {
+            tokens.push((*flag).to_owned());
+        }
+        tokens.extend(self.unknown.iter().cloned());
+
+        f.write_str(&tokens.join(" "))
+    }
+}
+
+/// Represents the position of a comment.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum CommentPosition {
+    /// Fixed at the top. (`ue`)
+    Ue,
+    /// Scrolling from right to left. This is the default. (`naka`)
+    Naka,
+    /// Fixed at the bottom. (`shita`)
+    Shita,
+}
+impl CommentPosition {
+    fn from_token(token: &str) -> Option<CommentPosition> {
+        match token {
+            "ue" => Some(CommentPosition::Ue),
+            "naka" => Some(CommentPosition::Naka),
+            "shita" => Some(CommentPosition::Shita),
+            _ => None,
+        }
+    }
+    fn token(self) -> &'static str {
+        match self {
+            CommentPosition::Ue => "ue",
+            CommentPosition::Naka => "naka",
+            CommentPosition::Shita => "shita",
+        }
+    }
+}
+
+/// Represents the size of a comment.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum CommentSize {
+    /// `big`
+    Big,
+    /// `medium`. This is the default.
+    Medium,
+    /// `small`
+    Small,
+}
+impl CommentSize {
+    fn from_token(token: &str) -> Option<CommentSize> {
+        match token {
+            "big" => Some(CommentSize::Big),
+            "medium" => Some(CommentSize::Medium),
+            "small" => Some(CommentSize::Small),
+            _ => None,
+        }
+    }
+    fn token(self) -> &'static str {
+        match self {
+            CommentSize::Big => "big",
+            CommentSize::Medium => "medium",
+            CommentSize::Small => "small",
+        }
+    }
+}
+
+/// Represents the font of a comment.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum CommentFont {
+    /// The default font. (`defont`)
+    Defont,
+    /// A serif font. (`mincho`)
+    Mincho,
+    /// A sans-serif font. (`gothic`)
+    Gothic,
+}
+impl CommentFont {
+    fn from_token(token: &str) -> Option<CommentFont> {
+        match token {
+            "defont" => Some(CommentFont::Defont),
+            "mincho" => Some(CommentFont::Mincho),
+            "gothic" => Some(CommentFont::Gothic),
+            _ => None,
+        }
+    }
+    fn token(self) -> &'static str {
+        match self {
+            CommentFont::Defont => "defont",
+            CommentFont::Mincho => "mincho",
+            CommentFont::Gothic => "gothic",
+        }
+    }
+}
+
+/// Represents the color of a comment.
+/// The variants suffixed with `2` are available only for premium users.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum CommentColor {
+    /// `white` (#FFFFFF). This is the default.
+    White,
+    /// `red` (#FF0000)
+    Red,
+    /// `pink` (#FF8080)
+    Pink,
+    /// `orange` (#FFC000)
+    Orange,
+    /// `yellow` (#FFFF00)
+    Yellow,
+    /// `green` (#00FF00)
+    Green,
+    /// `cyan` (#00FFFF)
+    Cyan,
+    /// `blue` (#0000FF)
+    Blue,
+    /// `purple` (#C000FF)
+    Purple,
+    /// `black` (#000000)
+    Black,
+    /// `white2` or `niconicowhite` (#CCCC99)
+    White2,
+    /// `red2` or `truered` (#CC0033)
+    Red2,
+    /// `pink2` (#FF33CC)
+    Pink2,
+    /// `orange2` or `passionorange` (#FF6600)
+    Orange2,
+    /// `yellow2` or `madyellow` (#999900)
+    Yellow2,
+    /// `green2` or `elementalgreen` (#00CC66)
+    Green2,
+    /// `cyan2` (#00CCCC)
+    Cyan2,
+    /// `blue2` or `marineblue` (#3399FF)
+    Blue2,
+    /// `purple2` or `nobleviolet` (#6633CC)
+    Purple2,
+    /// `black2` (#666666)
+    Black2,
+    /// A color code such as `#FF0000`.
+    Rgb(u8, u8, u8),
+}
+impl CommentColor {
+    const NAMED: [(CommentColor, &'static str, (u8, u8, u8)); 20] = [
+        (CommentColor::White, "white", (0xff, 0xff, 0xff)),
+        (CommentColor::Red, "red", (0xff, 0x00, 0x00)),
+        (CommentColor::Pink, "pink", (0xff, 0x80, 0x80)),
+        (CommentColor::Orange, "orange", (0xff, 0xc0, 0x00)),
+        (CommentColor::Yellow, "yellow", (0xff, 0xff, 0x00)),
+        (CommentColor::Green, "green", (0x00, 0xff, 0x00)),
+        (CommentColor::Cyan, "cyan", (0x00, 0xff, 0xff)),
+        (CommentColor::Blue, "blue", (0x00, 0x00, 0xff)),
+        (CommentColor::Purple, "purple", (0xc0, 0x00, 0xff)),
+        (CommentColor::Black, "black", (0x00, 0x00, 0x00)),
+        (CommentColor::White2, "white2", (0xcc, 0xcc, 0x99)),
+        (CommentColor::Red2, "red2", (0xcc, 0x00, 0x33)),
+        (CommentColor::Pink2, "pink2", (0xff, 0x33, 0xcc)),
+        (CommentColor::Orange2, "orange2", (0xff, 0x66, 0x00)),
+        (CommentColor::Yellow2, "yellow2", (0x99, 0x99, 0x00)),
+        (CommentColor::Green2, "green2", (0x00, 0xcc, 0x66)),
+        (CommentColor::Cyan2, "cyan2", (0x00, 0xcc, 0xcc)),
+        (CommentColor::Blue2, "blue2", (0x33, 0x99, 0xff)),
+        (CommentColor::Purple2, "purple2", (0x66, 0x33, 0xcc)),
+        (CommentColor::Black2, "black2", (0x66, 0x66, 0x66)),
+    ];
+
+    fn from_token(token: &str) -> Option<CommentColor> {
+        if let Some(hex) = token.strip_prefix('#') {
+            if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return None;
+            }
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+            return Some(CommentColor::Rgb(channel(0)?, channel(2)?, channel(4)?));
+        }
+        let token = match token {
+            "niconicowhite" => "white2",
+            "truered" => "red2",
+            "passionorange" => "orange2",
+            "madyellow" => "yellow2",
+            "elementalgreen" => "green2",
+            "marineblue" => "blue2",
+            "nobleviolet" => "purple2",
+            token => token,
+        };
+        CommentColor::NAMED
+            .iter()
+            .find(|(_, name, _)| *name == token)
+            .map(|(color, _, _)| *color)
+    }
+    fn token(self) -> String {
+        match self {
+            CommentColor::Rgb(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+            color => CommentColor::NAMED
+                .iter()
+                .find(|(named, _, _)| *named == color)
+                .map(|(_, name, _)| (*name).to_owned())
+                .unwrap_or_default(),
+        }
+    }
+    /// Returns the color as `(red, green, blue)`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use niconico::video::comments::commands::*;
+    /// assert_eq!(CommentColor::Orange.rgb(), (0xff, 0xc0, 0x00));
+    /// assert_eq!(CommentColor::Rgb(1, 2, 3).rgb(), (1, 2, 3));
+    /// ```
+    pub fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            CommentColor::Rgb(r, g, b) => (r, g, b),
+            color => CommentColor::NAMED
+                .iter()
+                .find(|(named, _, _)| *named == color)
+                .map(|(_, _, rgb)| *rgb)
+                .unwrap_or((0xff, 0xff, 0xff)),
+        }
+    }
+}
+
+/// Parses a duration command such as `@5` or `@2.5`.
+/// Returns `None` if the duration is negative or too long to be represented by `Duration`.
+fn parse_duration(token: &str) -> Option<Duration> {
+    let secs: f64 = token.strip_prefix('@')?.parse().ok()?;
+    // `Duration::from_secs_f64` panics on overflow; `2^64` is exactly representable as `f64`
+    if secs.is_finite() && secs >= 0.0 && secs < u64::MAX as f64 {
+        Some(Duration::from_secs_f64(secs))
+    } else {
+        None
+    }
+}
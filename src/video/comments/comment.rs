@@ -0,0 +1,68 @@
+use crate::*;
+use chrono::TimeZone;
+
+/// Represents a comment on a video.
+#[derive(Debug, Clone)]
+pub struct Comment {
+    /// The ID number of the thread this comment belongs to.
+    pub thread: u64,
+    /// The fork number of the thread. (`0`: main, `1`: owner, `2`: easy)
+    pub fork: u64,
+    /// The number of this comment in the thread. (コメント番号)
+    pub no: u64,
+    /// The playback position where this comment appears, in centiseconds.
+    pub vpos: i64,
+    /// The text of this comment.
+    pub body: String,
+    /// The commands of this comment, such as `184 ue red`.
+    pub commands: String,
+    /// The ID of the poster. Hashed if the comment is anonymous. Not available for some comments.
+    pub user_id: Option<String>,
+    /// The date when this comment was posted.
+    pub posted_at: DateTime<FixedOffset>,
+    /// The number of nicorus (ニコる) on this comment.
+    pub nicoru_count: u64,
+    /// The score of this comment used by the NG filter. Negative for comments which many users dislike.
+    pub score: i64,
+    /// Whether the poster is a premium user.
+    pub is_premium: bool,
+    /// Whether this comment is posted anonymously.
+    pub is_anonymous: bool,
+}
+impl Comment {
+    /// Parses the commands of this comment.
+    pub fn parse_commands(&self) -> super::commands::CommentCommands {
+        super::commands::CommentCommands::parse(&self.commands)
+    }
+    pub(crate) fn from_json(json: &serde_json::Value) -> Result<Comment> {
+        let date = json_extract!(json, as_i64, ["date"]);
+        let date_usec = json_extract_optional!(json, as_u64, ["date_usec"]).unwrap_or(0);
+        let posted_at = jst()
+            .timestamp_opt(date, (date_usec * 1000) as u32)
+            .single()
+            .context("`date` is invalid")
+            .context(Error::InvalidResponse)?;
+
+        Ok(Comment {
+            thread: json_extract!(json, as_str, ["thread"])
+                .parse()
+                .context(Error::InvalidResponse)?,
+            fork: json_extract_optional!(json, as_u64, ["fork"]).unwrap_or(0),
+            no: json_extract!(json, as_u64, ["no"]),
+            vpos: json_extract!(json, as_i64, ["vpos"]),
+            body: json_extract_optional!(json, as_string, ["content"]).unwrap_or_default(),
+            commands: json_extract_optional!(json, as_string, ["mail"]).unwrap_or_default(),
+            user_id: json_extract_optional!(json, as_string, ["user_id"]),
+            posted_at,
+            nicoru_count: json_extract_optional!(json, as_u64, ["nicoru"]).unwrap_or(0),
+            score: json_extract_optional!(json, as_i64, ["score"]).unwrap_or(0),
+            is_premium: json_extract_optional!(json, as_u64, ["premium"]) == Some(1),
+            is_anonymous: json_extract_optional!(json, as_u64, ["anonymity"]) == Some(1),
+        })
+    }
+}
+
+/// The time zone of niconico.
+pub(crate) fn jst() -> FixedOffset {
+    FixedOffset::east_opt(9 * 3600).unwrap()
+}
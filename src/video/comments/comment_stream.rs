@@ -0,0 +1,112 @@
+use super::{comment::Comment, *};
+use std::collections::VecDeque;
+
+type FetchCommentFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<(VecDeque<Comment>, bool, Option<(i64, u64)>)>> + 'a>>;
+
+/// Streams comments in a thread from the newest to the oldest. See also [comments::stream()](super::stream).
+pub struct CommentStream<'a> {
+    session: &'a Session,
+    params: &'a CommentParams,
+    thread: &'a CommentThread,
+    oldest: Option<(i64, u64)>,
+    future: Option<FetchCommentFuture<'a>>,
+    buf: VecDeque<Comment>,
+    is_finished: bool,
+}
+impl<'a> Stream for CommentStream<'a> {
+    type Item = Result<Comment>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        // a page can be empty after removing the comments received twice, so fetch until one is not
+        while self.buf.is_empty() && !self.is_finished {
+            if self.future.is_none() {
+                self.future = Some(Box::pin(Self::gen_future(
+                    self.session,
+                    self.params,
+                    self.thread,
+                    self.oldest,
+                )));
+            }
+            if let Some(future) = self.future.as_mut() {
+                match future.as_mut().poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(result) => {
+                        self.future = None;
+                        match result {
+                            Ok((buf, is_finished, oldest)) => {
+                                self.buf = buf;
+                                self.is_finished = is_finished;
+                                self.oldest = oldest;
+                            }
+                            Err(err) => return Poll::Ready(Some(Err(err))),
+                        }
+                    }
+                }
+            }
+        }
+
+        Poll::Ready(Ok(self.buf.pop_front()).transpose())
+    }
+}
+impl<'a> CommentStream<'a> {
+    pub fn new(
+        session: &'a Session,
+        params: &'a CommentParams,
+        thread: &'a CommentThread,
+    ) -> CommentStream<'a> {
+        CommentStream {
+            session,
+            params,
+            thread,
+            oldest: None,
+            future: None,
+            buf: VecDeque::new(),
+            is_finished: false,
+        }
+    }
+
+    /// Returns `(comments, is_finished, oldest)`. `oldest` is the date and the number of the oldest comment received.
+    async fn gen_future(
+        session: &'a Session,
+        params: &'a CommentParams,
+        thread: &'a CommentThread,
+        oldest: Option<(i64, u64)>,
+    ) -> Result<(VecDeque<Comment>, bool, Option<(i64, u64)>)> {
+        // comments posted in the same second as the oldest one are received again
+        let (when, oldest_no) = match oldest {
+            Some((date, no)) => (Some(date + 1), Some(no)),
+            None => (None, None),
+        };
+
+        // the leaves are for the latest comments, so request the thread itself
+        let thread = CommentThread {
+            is_leaf_required: false,
+            ..thread.clone()
+        };
+        let response = fetch_thread(session, params, &thread, when).await?;
+
+        // decide from the response itself, since all the comments can be received twice
+        let is_finished = match response.comments.iter().map(|comment| comment.no).min() {
+            Some(no) => no <= 1,
+            None => true,
+        };
+
+        let comments: VecDeque<Comment> = response
+            .comments
+            .into_iter()
+            .rev()
+            .filter(|comment| match oldest_no {
+                Some(no) => comment.no < no,
+                None => true,
+            })
+            .collect();
+
+        let oldest = match comments.back() {
+            Some(comment) => Some((comment.posted_at.timestamp(), comment.no)),
+            // more comments than a page were posted in the same second, so skip the second
+            None => oldest.map(|(date, no)| (date - 1, no)),
+        };
+
+        Ok((comments, is_finished, oldest))
+    }
+}
@@ -0,0 +1,380 @@
+use crate::*;
+use std::time::Duration;
+
+pub mod ass;
+pub mod commands;
+pub mod comment;
+pub mod comment_stream;
+pub mod post;
+pub mod xml;
+
+pub use comment::Comment;
+
+/// Fetches the parameters required to fetch comments from the watch page of the video.
+///
+/// # Examples
+/// ```no_run
+/// use futures::StreamExt;
+/// # use niconico::*;
+/// use video::comments::{self, CommentThreadKind};
+/// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// # let mut session = Session::new(USER_AGENT, Language::Japanese);
+/// # session.set_cookie_user_session(&std::env::var("NICO_SID")?);
+/// let params = comments::fetch_params(&session, &"sm9".parse()?).await?;
+/// let main_thread = params.thread(CommentThreadKind::Main).unwrap();
+///
+/// // the latest comments
+/// for comment in comments::fetch(&session, &params, main_thread).await? {
+///     println!("{}", comment.body);
+/// }
+///
+/// // all comments, from the newest to the oldest
+/// let mut comment_stream = comments::stream(&session, &params, main_thread);
+/// while let Some(comment) = comment_stream.next().await {
+///     println!("{}", comment?.body);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn fetch_params(session: &Session, id: &VideoId) -> Result<CommentParams> {
+    let data = super::fetch_watch_data(session, id).await?;
+    CommentParams::from_json(&data)
+}
+/// Fetches the latest comments in the thread. See [`fetch_params`] for examples.
+///
+/// The thread leaves (the comments distributed over the video) are fetched if the thread requires them.
+pub async fn fetch(
+    session: &Session,
+    params: &CommentParams,
+    thread: &CommentThread,
+) -> Result<Vec<Comment>> {
+    let response = fetch_thread(session, params, thread, None).await?;
+    Ok(response.comments)
+}
+/// Creates stream of all comments in the thread, from the newest to the oldest.
+/// See [`fetch_params`] for examples.
+pub fn stream<'a>(
+    session: &'a Session,
+    params: &'a CommentParams,
+    thread: &'a CommentThread,
+) -> comment_stream::CommentStream<'a> {
+    comment_stream::CommentStream::new(session, params, thread)
+}
+
+/// Posts a comment to the thread and returns the number assigned to the comment.
+///
+/// `vpos` is the playback position where the comment appears, in centiseconds.
+///
+/// # Errors
+/// - `Error::LoginRequired` if the session is not logged in.
+/// - `Error::CommentPostingRestricted` if the thread does not accept comments.
+/// - `Error::CommentRejected` if the comment is rejected, for example by the NG filter.
+///
+/// # Examples
+/// ```no_run
+/// # use niconico::*;
+/// use video::comments::{self, commands::CommentCommands, CommentThreadKind};
+/// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// # let mut session = Session::new(USER_AGENT, Language::Japanese);
+/// # session.set_cookie_user_session(&std::env::var("NICO_SID")?);
+/// let params = comments::fetch_params(&session, &"sm9".parse()?).await?;
+/// let thread = params.default_post_target().unwrap();
+///
+/// let commands = CommentCommands::parse("184 ue");
+/// let no = comments::post(&session, &params, thread, "hello", 1500, &commands).await?;
+/// println!("posted as No.{}", no);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn post(
+    session: &Session,
+    params: &CommentParams,
+    thread: &CommentThread,
+    body: &str,
+    vpos: i64,
+    commands: &commands::CommentCommands,
+) -> Result<u64> {
+    post::post(session, params, thread, body, vpos, commands).await
+}
+
+/// Represents the parameters required to fetch comments of a video.
+#[derive(Debug, Clone)]
+pub struct CommentParams {
+    /// The ID of the video.
+    pub video_id: VideoId,
+    /// The length of the video.
+    pub duration: Duration,
+    /// The comment threads of the video.
+    pub threads: Vec<CommentThread>,
+    /// The ID number of the login user. `None` if not logged in.
+    pub user_id: Option<u64>,
+    /// The key to authorize the login user on the comment server.
+    pub user_key: String,
+    /// Whether the login user is a premium user.
+    pub is_premium: bool,
+}
+impl CommentParams {
+    pub(crate) fn from_json(data: &serde_json::Value) -> Result<CommentParams> {
+        let mut threads = Vec::new();
+        for thread in json_extract!(data, as_array, ["comment"]["threads"]) {
+            threads.push(CommentThread::from_json(thread)?);
+        }
+
+        Ok(CommentParams {
+            video_id: json_extract!(data, as_str, ["video"]["id"]).parse()?,
+            duration: Duration::from_secs(json_extract!(data, as_u64, ["video"]["duration"])),
+            threads,
+            user_id: json_extract_optional!(data, as_u64, ["viewer"]["id"]),
+            user_key: json_extract_optional!(data, as_string, ["comment"]["keys"]["userKey"])
+                .unwrap_or_default(),
+            is_premium: json_extract_optional!(data, as_bool, ["viewer"]["isPremium"])
+                .unwrap_or(false),
+        })
+    }
+    /// Returns the first active thread of the kind.
+    pub fn thread(&self, kind: CommentThreadKind) -> Option<&CommentThread> {
+        self.threads
+            .iter()
+            .find(|thread| thread.is_active && thread.kind == kind)
+    }
+    /// Returns the thread comments are posted to by default.
+    pub fn default_post_target(&self) -> Option<&CommentThread> {
+        self.threads
+            .iter()
+            .find(|thread| thread.is_active && thread.is_default_post_target)
+    }
+}
+
+/// Represents a comment thread of a video.
+#[derive(Debug, Clone)]
+pub struct CommentThread {
+    /// The ID number of this thread.
+    pub id: u64,
+    /// The fork number of this thread. (`0`: main, `1`: owner, `2`: easy)
+    pub fork: u64,
+    /// The kind of this thread.
+    pub kind: CommentThreadKind,
+    /// Whether this thread is in use.
+    pub is_active: bool,
+    /// Whether comments are posted to this thread by default.
+    pub is_default_post_target: bool,
+    /// Whether easy comments (かんたんコメント) are posted to this thread.
+    pub is_easy_comment_post_target: bool,
+    /// Whether the comments of this thread have to be fetched as leaves.
+    pub is_leaf_required: bool,
+    /// Whether a thread key is required to fetch the comments of this thread.
+    pub is_threadkey_required: bool,
+    /// The URL of the comment server of this thread.
+    pub server_url: String,
+}
+impl CommentThread {
+    pub(crate) fn from_json(json: &serde_json::Value) -> Result<CommentThread> {
+        Ok(CommentThread {
+            id: json_extract!(json, as_u64, ["id"]),
+            fork: json_extract!(json, as_u64, ["fork"]),
+            kind: match json_extract!(json, as_str, ["label"]) {
+                "default" => CommentThreadKind::Main,
+                "owner" => CommentThreadKind::Owner,
+                "easy" | "extra-easy" => CommentThreadKind::Easy,
+                "community" | "extra-community" => CommentThreadKind::Community,
+                _ => CommentThreadKind::Other,
+            },
+            is_active: json_extract!(json, as_bool, ["isActive"]),
+            is_default_post_target: json_extract!(json, as_bool, ["isDefaultPostTarget"]),
+            is_easy_comment_post_target: json_extract!(json, as_bool, ["isEasyCommentPostTarget"]),
+            is_leaf_required: json_extract!(json, as_bool, ["isLeafRequired"]),
+            is_threadkey_required: json_extract!(json, as_bool, ["isThreadkeyRequired"]),
+            server_url: json_extract!(json, as_string, ["server"]),
+        })
+    }
+}
+/// Represents a kind of a comment thread.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum CommentThreadKind {
+    /// The thread where viewers comment.
+    Main,
+    /// The thread of the comments by the uploader. (投稿者コメント)
+    Owner,
+    /// The thread of easy comments. (かんたんコメント)
+    Easy,
+    /// The thread of the comments in the community or the channel.
+    Community,
+    /// Other threads.
+    Other,
+}
+
+/// A response of the comment server to a thread request.
+pub(crate) struct ThreadResponse {
+    pub comments: Vec<Comment>,
+    /// The ticket required to post a comment.
+    pub ticket: Option<String>,
+    /// The number of the last comment in the thread.
+    pub last_res: Option<u64>,
+}
+
+/// Fetches comments in the thread posted before `when` (unix time) or the latest comments if `when` is `None`.
+pub(crate) async fn fetch_thread(
+    session: &Session,
+    params: &CommentParams,
+    thread: &CommentThread,
+    when: Option<i64>,
+) -> Result<ThreadResponse> {
+    let user_id = params.user_id.map(|id| id.to_string()).unwrap_or_default();
+
+    let mut thread_command = serde_json::json!({
+        "thread": thread.id.to_string(),
+        "version": "20090904",
+        "fork": thread.fork,
+        "language": 0,
+        "user_id": user_id,
+        "with_global": 1,
+        "scores": 1,
+        "nicoru": 3,
+    });
+    if thread.is_threadkey_required {
+        let (threadkey, force_184) = fetch_threadkey(session, thread).await?;
+        thread_command["threadkey"] = threadkey.into();
+        thread_command["force_184"] = force_184.into();
+    } else {
+        thread_command["userkey"] = params.user_key.clone().into();
+    }
+    if let Some(when) = when {
+        thread_command["when"] = when.into();
+        if session.is_logged_in() {
+            thread_command["waybackkey"] = fetch_waybackkey(session, thread).await?.into();
+        }
+    }
+
+    let mut commands = vec![
+        serde_json::json!({ "ping": { "content": "rs:0" } }),
+        serde_json::json!({ "ping": { "content": "ps:0" } }),
+    ];
+    if thread.is_leaf_required && when.is_none() {
+        let minutes = (params.duration.as_secs() + 59) / 60;
+        let mut leaves_command = thread_command.clone();
+        leaves_command["content"] = format!("0-{}:100,1000,nicoru:100", minutes).into();
+        if let Some(obj) = leaves_command.as_object_mut() {
+            obj.remove("version");
+            obj.remove("fork");
+            obj.remove("with_global");
+        }
+        commands.push(serde_json::json!({ "thread": thread_command }));
+        commands.push(serde_json::json!({ "ping": { "content": "pf:0" } }));
+        commands.push(serde_json::json!({ "ping": { "content": "ps:1" } }));
+        commands.push(serde_json::json!({ "thread_leaves": leaves_command }));
+        commands.push(serde_json::json!({ "ping": { "content": "pf:1" } }));
+    } else {
+        thread_command["res_from"] = (-1000).into();
+        commands.push(serde_json::json!({ "thread": thread_command }));
+        commands.push(serde_json::json!({ "ping": { "content": "pf:0" } }));
+    }
+    commands.push(serde_json::json!({ "ping": { "content": "rf:0" } }));
+
+    let json = session
+        .send_json(
+            session
+                .post(&json_api_url(&thread.server_url), None)
+                .header("Content-Type", "text/plain;charset=UTF-8")
+                .body(serde_json::Value::from(commands).to_string()),
+        )
+        .await
+        .context("cannot fetch comments")?;
+
+    let mut response = ThreadResponse {
+        comments: Vec::new(),
+        ticket: None,
+        last_res: None,
+    };
+    let items = json
+        .as_array()
+        .context("the response is not an array")
+        .context(Error::InvalidResponse)?;
+    for item in items {
+        if let Some(thread) = item.get("thread") {
+            let result_code = json_extract!(thread, as_u64, ["resultcode"]);
+            ensure!(
+                result_code == 0,
+                anyhow!("thread result code: {}", result_code).context(Error::InvalidResponse)
+            );
+            if response.ticket.is_none() {
+                response.ticket = json_extract_optional!(thread, as_string, ["ticket"]);
+            }
+            if response.last_res.is_none() {
+                response.last_res = json_extract_optional!(thread, as_u64, ["last_res"]);
+            }
+        } else if let Some(chat) = item.get("chat") {
+            if chat.get("deleted").is_none() {
+                response.comments.push(Comment::from_json(chat)?);
+            }
+        }
+    }
+    // leaves and the thread can contain the same comment
+    response.comments.sort_by_key(|comment| comment.no);
+    response.comments.dedup_by_key(|comment| comment.no);
+
+    Ok(response)
+}
+
+/// Converts the URL of a comment server (`.../api/`) to the URL of its json API (`.../api.json/`).
+fn json_api_url(server_url: &str) -> String {
+    let server_url = server_url.trim_end_matches('/');
+    if let Some(base) = server_url.strip_suffix("/api") {
+        format!("{}/api.json/", base)
+    } else {
+        format!("{}/", server_url)
+    }
+}
+
+/// Fetches the thread key and `force_184` of the thread.
+async fn fetch_threadkey(session: &Session, thread: &CommentThread) -> Result<(String, String)> {
+    let url = format!(
+        "{}/api/getthreadkey?thread={}",
+        session.endpoints().flapi,
+        thread.id
+    );
+    let response = session
+        .get_text(&url, None)
+        .await
+        .context("cannot fetch the thread key")?;
+    let (mut threadkey, mut force_184) = (None, String::new());
+    for (key, value) in parse_query(&response) {
+        match key {
+            "threadkey" => threadkey = Some(value.to_owned()),
+            "force_184" => force_184 = value.to_owned(),
+            _ => (),
+        }
+    }
+    let threadkey = threadkey
+        .context("`threadkey` is missing")
+        .context(Error::InvalidResponse)?;
+    Ok((threadkey, force_184))
+}
+/// Fetches the key to fetch past comments of the thread.
+async fn fetch_waybackkey(session: &Session, thread: &CommentThread) -> Result<String> {
+    let url = format!(
+        "{}/api/getwaybackkey?thread={}",
+        session.endpoints().flapi,
+        thread.id
+    );
+    let response = session
+        .get_text(&url, None)
+        .await
+        .context("cannot fetch the wayback key")?;
+    let waybackkey = parse_query(&response)
+        .find(|(key, _)| *key == "waybackkey")
+        .map(|(_, value)| value.to_owned())
+        .context("`waybackkey` is missing")
+        .context(Error::InvalidResponse)?;
+    Ok(waybackkey)
+}
+/// Splits a response of flapi such as `key1=value1&key2=value2`.
+pub(crate) fn parse_query(query: &str) -> impl Iterator<Item = (&str, &str)> {
+    query.trim().split('&').filter_map(|pair| {
+        let mut pair = pair.splitn(2, '=');
+        Some((pair.next()?, pair.next().unwrap_or_default()))
+    })
+}
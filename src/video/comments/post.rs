@@ -0,0 +1,122 @@
+use super::{commands::CommentCommands, *};
+
+/// Posts a comment. See also [`comments::post`](super::post).
+pub(crate) async fn post(
+    session: &Session,
+    params: &CommentParams,
+    thread: &CommentThread,
+    body: &str,
+    vpos: i64,
+    commands: &CommentCommands,
+) -> Result<u64> {
+    let user_id = match params.user_id {
+        Some(user_id) if session.is_logged_in() => user_id,
+        _ => bail!(Error::LoginRequired),
+    };
+
+    // the ticket and the number of the last comment are required to post
+    let thread_without_leaves = CommentThread {
+        is_leaf_required: false,
+        ..thread.clone()
+    };
+    let response = fetch_thread(session, params, &thread_without_leaves, None).await?;
+    let ticket = response
+        .ticket
+        .context("`ticket` is missing")
+        .context(Error::InvalidResponse)?;
+    let last_res = response.last_res.unwrap_or(0);
+
+    let postkey = fetch_postkey(session, thread, last_res).await?;
+
+    let commands = vec![
+        serde_json::json!({ "ping": { "content": "rs:0" } }),
+        serde_json::json!({ "ping": { "content": "ps:0" } }),
+        serde_json::json!({
+            "chat": {
+                "thread": thread.id.to_string(),
+                "vpos": vpos,
+                "mail": commands.to_string(),
+                "ticket": ticket,
+                "user_id": user_id.to_string(),
+                "content": body,
+                "postkey": postkey,
+                "premium": if params.is_premium { 1 } else { 0 },
+            }
+        }),
+        serde_json::json!({ "ping": { "content": "pf:0" } }),
+        serde_json::json!({ "ping": { "content": "rf:0" } }),
+    ];
+    let json = session
+        .send_json(
+            session
+                .post(&json_api_url(&thread.server_url), None)
+                .header("Content-Type", "text/plain;charset=UTF-8")
+                .body(serde_json::Value::from(commands).to_string()),
+        )
+        .await
+        .context("cannot post the comment")?;
+
+    parse_post_result(&json)
+}
+
+/// Parses the response to posting a comment, and returns the number of the posted comment.
+///
+/// # Errors
+/// - `Error::CommentRejected` if the comment was rejected.
+/// - `Error::CommentPostingRestricted` if comments cannot be posted to the thread.
+///
+/// # Examples
+/// ```
+/// # use niconico::{*, video::comments::post::parse_post_result};
+/// let response = |status: u64| serde_json::json!([
+///     { "ping": { "content": "rs:0" } },
+///     { "chat_result": { "thread": "1173108780", "status": status, "no": 42 } },
+///     { "ping": { "content": "rf:0" } },
+/// ]);
+/// assert_eq!(parse_post_result(&response(0))?, 42);
+///
+/// let error = |status: u64| parse_post_result(&response(status)).unwrap_err();
+/// for status in &[1, 8] {
+///     assert!(matches!(error(*status).downcast_ref::<Error>(), Some(Error::CommentRejected)));
+/// }
+/// for status in &[5, 6] {
+///     assert!(matches!(error(*status).downcast_ref::<Error>(), Some(Error::CommentPostingRestricted)));
+/// }
+/// assert!(matches!(error(3).downcast_ref::<Error>(), Some(Error::InvalidResponse)));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_post_result(json: &serde_json::Value) -> Result<u64> {
+    let chat_result = json
+        .as_array()
+        .and_then(|items| items.iter().find_map(|item| item.get("chat_result")))
+        .context("`chat_result` is missing")
+        .context(Error::InvalidResponse)?;
+    let status = json_extract!(chat_result, as_u64, ["status"]);
+    match status {
+        0 => Ok(json_extract!(chat_result, as_u64, ["no"])),
+        1 | 8 => bail!(Error::CommentRejected),
+        5 | 6 => bail!(Error::CommentPostingRestricted),
+        status => bail!(anyhow!("chat result status: {}", status).context(Error::InvalidResponse)),
+    }
+}
+
+/// Fetches the key to post a comment after the comment numbered `last_res`.
+async fn fetch_postkey(session: &Session, thread: &CommentThread, last_res: u64) -> Result<String> {
+    let url = format!(
+        "{}/api/getpostkey?thread={}&block_no={}&device=1&version=1&version_sub=6",
+        session.endpoints().flapi,
+        thread.id,
+        (last_res + 1) / 100
+    );
+    let response = session
+        .get_text(&url, None)
+        .await
+        .context("cannot fetch the post key")?;
+    let postkey = parse_query(&response)
+        .find(|(key, _)| *key == "postkey")
+        .map(|(_, value)| value.to_owned())
+        .unwrap_or_default();
+    // an empty post key is returned for threads which do not accept comments
+    ensure!(!postkey.is_empty(), Error::CommentPostingRestricted);
+    Ok(postkey)
+}
@@ -0,0 +1,186 @@
+use super::{comment::jst, Comment};
+use crate::*;
+use chrono::TimeZone;
+use std::collections::BTreeMap;
+
+/// Converts comments to the legacy niconico XML format (`<packet><chat>...</chat></packet>`).
+///
+/// # Examples
+/// ```
+/// # use niconico::video::comments::{xml, Comment};
+/// # use chrono::{DateTime, FixedOffset};
+/// let comments = vec![Comment {
+///     thread: 1173108780,
+///     fork: 0,
+///     no: 1,
+///     vpos: 150,
+///     body: "<hello & world>".to_owned(),
+///     commands: "184 red".to_owned(),
+///     user_id: Some("abc".to_owned()),
+///     posted_at: DateTime::<FixedOffset>::parse_from_rfc3339("2007-03-06T00:33:00+09:00").unwrap(),
+///     nicoru_count: 0,
+///     score: 0,
+///     is_premium: true,
+///     is_anonymous: true,
+/// }];
+///
+/// let xml = xml::to_xml(&comments);
+/// assert!(xml.contains(r#"<chat thread="1173108780" no="1" vpos="150" date="1173108780""#));
+/// assert!(xml.contains("&lt;hello &amp; world&gt;</chat>"));
+///
+/// let parsed = xml::from_xml(&xml)?;
+/// assert_eq!(parsed[0].body, "<hello & world>");
+/// assert_eq!(parsed[0].posted_at, comments[0].posted_at);
+///
+/// let broken = r#"<packet><chat thread="1" no="1" vpos="0" date="0" date_usec="4294968">a</chat></packet>"#;
+/// assert!(xml::from_xml(broken).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn to_xml(comments: &[Comment]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<packet>\n");
+    for comment in comments {
+        xml += &format!(
+            "<chat thread=\"{}\" no=\"{}\" vpos=\"{}\" date=\"{}\" date_usec=\"{}\"",
+            comment.thread,
+            comment.no,
+            comment.vpos,
+            comment.posted_at.timestamp(),
+            comment.posted_at.timestamp_subsec_micros()
+        );
+        if comment.fork != 0 {
+            xml += &format!(" fork=\"{}\"", comment.fork);
+        }
+        if !comment.commands.is_empty() {
+            xml += &format!(" mail=\"{}\"", escape(&comment.commands));
+        }
+        if let Some(user_id) = &comment.user_id {
+            xml += &format!(" user_id=\"{}\"", escape(user_id));
+        }
+        if comment.is_premium {
+            xml += " premium=\"1\"";
+        }
+        if comment.is_anonymous {
+            xml += " anonymity=\"1\"";
+        }
+        if comment.nicoru_count != 0 {
+            xml += &format!(" nicoru=\"{}\"", comment.nicoru_count);
+        }
+        if comment.score != 0 {
+            xml += &format!(" score=\"{}\"", comment.score);
+        }
+        xml += &format!(">{}</chat>\n", escape(&comment.body));
+    }
+    xml += "</packet>\n";
+    xml
+}
+
+/// Parses comments in the legacy niconico XML format. Deleted comments are skipped.
+/// See [`to_xml`] for examples.
+pub fn from_xml(xml: &str) -> Result<Vec<Comment>> {
+    let doc = roxmltree::Document::parse(xml).context(Error::InvalidResponse)?;
+
+    let mut comments = Vec::new();
+    for chat in doc
+        .root_element()
+        .children()
+        .filter(|n| n.has_tag_name("chat"))
+    {
+        if chat.attribute("deleted").is_some() {
+            continue;
+        }
+
+        let date: i64 = parse_attr(chat, "date")?;
+        let date_usec: u32 = parse_attr_optional(chat, "date_usec")?.unwrap_or(0);
+        ensure!(
+            date_usec < 1_000_000,
+            anyhow!("`date_usec` is out of range: {}", date_usec).context(Error::InvalidResponse)
+        );
+        let posted_at = jst()
+            .timestamp_opt(date, date_usec * 1000)
+            .single()
+            .context("`date` is invalid")
+            .context(Error::InvalidResponse)?;
+
+        comments.push(Comment {
+            thread: parse_attr(chat, "thread")?,
+            fork: parse_attr_optional(chat, "fork")?.unwrap_or(0),
+            no: parse_attr(chat, "no")?,
+            vpos: parse_attr(chat, "vpos")?,
+            body: chat.text().unwrap_or_default().to_owned(),
+            commands: chat.attribute("mail").unwrap_or_default().to_owned(),
+            user_id: chat.attribute("user_id").map(|s| s.to_owned()),
+            posted_at,
+            nicoru_count: parse_attr_optional(chat, "nicoru")?.unwrap_or(0),
+            score: parse_attr_optional(chat, "score")?.unwrap_or(0),
+            is_premium: chat.attribute("premium") == Some("1"),
+            is_anonymous: chat.attribute("anonymity") == Some("1"),
+        });
+    }
+    Ok(comments)
+}
+
+/// Merges comments from several sources, such as old XML dumps and new fetches.
+///
+/// Comments are identified by `(thread, fork, no)`, since the owner comments of a thread
+/// are numbered apart from the others. If a comment appears more than once,
+/// the last one is kept so that newer data (for example, nicoru counts) wins.
+/// The result is sorted by `(thread, fork, no)`.
+///
+/// # Examples
+/// ```
+/// # use niconico::video::comments::{xml, Comment};
+/// # use chrono::{DateTime, FixedOffset};
+/// # fn comment(thread: u64, no: u64, nicoru_count: u64) -> Comment {
+/// #     Comment {
+/// #         thread, fork: 0, no, vpos: 0, body: String::new(), commands: String::new(),
+/// #         user_id: None, posted_at: DateTime::<FixedOffset>::parse_from_rfc3339("2007-03-06T00:33:00+09:00").unwrap(),
+/// #         nicoru_count, score: 0, is_premium: false, is_anonymous: false,
+/// #     }
+/// # }
+/// let archived = vec![comment(1, 1, 0), comment(1, 2, 0)];
+/// let fetched = vec![comment(1, 2, 5), comment(1, 3, 0), comment(2, 1, 0)];
+/// // an owner comment which has the same number as another comment
+/// let owner = vec![Comment { fork: 1, ..comment(1, 2, 0) }];
+///
+/// let merged = xml::merge(vec![archived, fetched, owner]);
+/// let keys: Vec<_> = merged.iter().map(|c| (c.thread, c.fork, c.no)).collect();
+/// assert_eq!(keys, vec![(1, 0, 1), (1, 0, 2), (1, 0, 3), (1, 1, 2), (2, 0, 1)]);
+/// assert_eq!(merged[1].nicoru_count, 5);
+/// ```
+pub fn merge(sources: impl IntoIterator<Item = Vec<Comment>>) -> Vec<Comment> {
+    let mut merged = BTreeMap::new();
+    for comment in sources.into_iter().flatten() {
+        merged.insert((comment.thread, comment.fork, comment.no), comment);
+    }
+    merged.into_values().collect()
+}
+
+fn parse_attr<T>(node: roxmltree::Node, name: &str) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    parse_attr_optional(node, name)?
+        .with_context(|| format!("`{}` is missing", name))
+        .context(Error::InvalidResponse)
+}
+fn parse_attr_optional<T>(node: roxmltree::Node, name: &str) -> Result<Option<T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    node.attribute(name)
+        .map(|value| {
+            value
+                .parse()
+                .with_context(|| format!("`{}` is invalid", name))
+                .context(Error::InvalidResponse)
+        })
+        .transpose()
+}
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
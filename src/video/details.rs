@@ -0,0 +1,139 @@
+use crate::*;
+use std::time::Duration;
+
+/// Represents details of a video.
+#[derive(Debug, Clone)]
+pub struct VideoDetails {
+    /// The ID of this video.
+    pub id: VideoId,
+    /// The title of this video.
+    pub title: String,
+    /// The description of this video, decorated with HTML.
+    pub description: String,
+    /// The length of this video.
+    pub duration: Duration,
+    /// The date when this video was uploaded.
+    pub registered_at: DateTime<FixedOffset>,
+    /// The number of times this video has been viewed.
+    pub view_count: u64,
+    /// The number of comments on this video.
+    pub comment_count: u64,
+    /// The number of mylists this video is registered to.
+    pub mylist_count: u64,
+    /// The number of likes (いいね！) of this video.
+    pub like_count: u64,
+    /// The tags of this video.
+    pub tags: Vec<VideoTag>,
+    /// The uploader of this video. `None` if the uploader has left niconico.
+    pub owner: Option<VideoOwner>,
+    /// The thumbnails of this video.
+    pub thumbnails: VideoThumbnails,
+}
+impl VideoDetails {
+    /// Fetches the details of the video. See also [`VideoId::fetch_details`](super::VideoId::fetch_details).
+    pub async fn fetch(session: &Session, id: &VideoId) -> Result<VideoDetails> {
+        let data = super::fetch_watch_data(session, id).await?;
+        VideoDetails::from_json(&data)
+    }
+    pub(crate) fn from_json(data: &serde_json::Value) -> Result<VideoDetails> {
+        let video = &data["video"];
+
+        let mut tags = Vec::new();
+        for tag in json_extract!(data, as_array, ["tag"]["items"]) {
+            tags.push(VideoTag {
+                name: json_extract!(tag, as_string, ["name"]),
+                is_locked: json_extract!(tag, as_bool, ["isLocked"]),
+            });
+        }
+
+        let owner = if !data["owner"].is_null() {
+            Some(VideoOwner::User {
+                user: User::UserId(json_extract!(data, as_u64, ["owner"]["id"])),
+                nickname: json_extract!(data, as_string, ["owner"]["nickname"]),
+                icon_url: json_extract!(data, as_string, ["owner"]["iconUrl"]),
+            })
+        } else if !data["channel"].is_null() {
+            Some(VideoOwner::Channel {
+                id: json_extract!(data, as_str, ["channel"]["id"])
+                    .trim_start_matches("ch")
+                    .parse()
+                    .context(Error::InvalidResponse)?,
+                name: json_extract!(data, as_string, ["channel"]["name"]),
+                icon_url: json_extract!(data, as_string, ["channel"]["thumbnail"]["url"]),
+            })
+        } else {
+            None
+        };
+
+        Ok(VideoDetails {
+            id: json_extract!(video, as_str, ["id"]).parse()?,
+            title: json_extract!(video, as_string, ["title"]),
+            description: json_extract!(video, as_string, ["description"]),
+            duration: Duration::from_secs(json_extract!(video, as_u64, ["duration"])),
+            registered_at: DateTime::<FixedOffset>::parse_from_rfc3339(json_extract!(
+                video,
+                as_str,
+                ["registeredAt"]
+            ))
+            .context(Error::InvalidResponse)?,
+            view_count: json_extract!(video, as_u64, ["count"]["view"]),
+            comment_count: json_extract!(video, as_u64, ["count"]["comment"]),
+            mylist_count: json_extract!(video, as_u64, ["count"]["mylist"]),
+            like_count: json_extract!(video, as_u64, ["count"]["like"]),
+            tags,
+            owner,
+            thumbnails: VideoThumbnails {
+                url: json_extract!(video, as_string, ["thumbnail"]["url"]),
+                middle_url: json_extract_optional!(video, as_string, ["thumbnail"]["middleUrl"]),
+                large_url: json_extract_optional!(video, as_string, ["thumbnail"]["largeUrl"]),
+                player_url: json_extract_optional!(video, as_string, ["thumbnail"]["player"]),
+                ogp_url: json_extract_optional!(video, as_string, ["thumbnail"]["ogp"]),
+            },
+        })
+    }
+}
+
+/// Represents a tag of a video.
+#[derive(Debug, Clone)]
+pub struct VideoTag {
+    /// The name of the tag.
+    pub name: String,
+    /// Whether the tag is locked by the uploader.
+    pub is_locked: bool,
+}
+/// Represents the uploader of a video.
+#[derive(Debug, Clone)]
+pub enum VideoOwner {
+    /// A user.
+    User {
+        /// The `User` that represents the uploader.
+        user: User,
+        /// The nickname of the uploader.
+        nickname: String,
+        /// The URL of the profile icon of the uploader.
+        icon_url: String,
+    },
+    /// A channel. (ニコニコチャンネル)
+    Channel {
+        /// The ID number of the channel. (without `ch`)
+        id: u64,
+        /// The name of the channel.
+        name: String,
+        /// The URL of the icon of the channel.
+        icon_url: String,
+    },
+}
+/// Represents the thumbnails of a video.
+#[derive(Debug, Clone)]
+pub struct VideoThumbnails {
+    /// The URL of the normal thumbnail. (130x100)
+    pub url: String,
+    /// The URL of the middle thumbnail. (320x180)
+    pub middle_url: Option<String>,
+    /// The URL of the large thumbnail. (640x360)
+    pub large_url: Option<String>,
+    /// The URL of the image shown in the player before playback.
+    pub player_url: Option<String>,
+    /// The URL of the image for OGP.
+    pub ogp_url: Option<String>,
+}
@@ -0,0 +1,685 @@
+//! Downloads HLS content, such as the one negotiated with [`stream::negotiate`](super::stream::negotiate).
+//!
+//! Segments are concatenated as they are, so the output is an MPEG-TS or fragmented MP4 stream.
+//! If the chosen variant has its audio in a separate rendition (`#EXT-X-MEDIA:TYPE=AUDIO`),
+//! as domand streams do, the audio is written to a second output, which can be muxed with the video by tools such as ffmpeg.
+
+use crate::*;
+use futures::{stream, StreamExt};
+use playlist::{Playlist, Segment};
+use std::{
+    collections::HashMap,
+    io::SeekFrom,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+pub mod playlist;
+
+/// Options for [`to_file`] and [`to_writer`].
+#[derive(Debug, Clone)]
+pub struct DownloadOptions {
+    /// How many segments are fetched at the same time.
+    pub concurrency: usize,
+    /// Which variant to download if the URL points to a master playlist.
+    pub quality: super::stream::QualityPreference,
+    /// The value of header `Cookie` required to fetch the content, such as [`StreamSession::cookie`](super::stream::StreamSession::cookie).
+    pub cookie: Option<String>,
+    /// The video the content belongs to. The URL differs for each session,
+    /// so [`to_file`] resumes a download only if this and the chosen variant match the previous one.
+    /// If `None`, the download is not resumed.
+    pub video_id: Option<VideoId>,
+}
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        DownloadOptions {
+            concurrency: 4,
+            quality: super::stream::QualityPreference::Highest,
+            cookie: None,
+            video_id: None,
+        }
+    }
+}
+
+/// Represents the progress of a download.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DownloadEvent {
+    /// The playlist is fetched and the download is started.
+    Started {
+        /// The number of the segments, including the ones of the audio.
+        total_segments: usize,
+        /// The number of the segments downloaded by a previous download, which are skipped.
+        completed_segments: usize,
+        /// Whether the audio is in a separate rendition, which is written to the second output.
+        has_separate_audio: bool,
+    },
+    /// A segment is written.
+    SegmentDownloaded {
+        /// The index of the segment. The segments of the video come first, followed by the ones of the audio.
+        index: usize,
+        /// The size of the segment in bytes, after decryption.
+        bytes: u64,
+        /// The number of the segments.
+        total_segments: usize,
+    },
+    /// All the segments are written.
+    Finished {
+        /// The size of the whole content in bytes, including the audio.
+        bytes: u64,
+    },
+}
+
+/// A stream of the progress of a download. The download proceeds as the stream is polled.
+/// The stream ends after [`DownloadEvent::Finished`] or the first error.
+pub type DownloadStream<'a> = Pin<Box<dyn Stream<Item = Result<DownloadEvent>> + 'a>>;
+
+/// Downloads the HLS content at `url` to the file at `path`.
+/// If the audio is in a separate rendition, it is written to the file at [`audio_path(path)`](audio_path).
+///
+/// If [`DownloadOptions::video_id`] is specified, the progress is saved to a state file next to the file (`{path}.download.json`).
+/// If the download is interrupted, downloading the same video to the same path again resumes it from
+/// the first segment which has not been written, as long as the same variant is chosen.
+/// The state file is removed when the download finishes.
+///
+/// # Examples
+/// ```no_run
+/// # async fn run() -> anyhow::Result<()> {
+/// # use niconico::*;
+/// # use niconico::video::{download::{self, DownloadEvent, DownloadOptions}, stream::{self, StreamOptions}};
+/// # use futures::StreamExt;
+/// let session = Session::new("user agent", Language::English);
+/// let id: VideoId = "sm9".parse()?;
+/// let mut stream_session = stream::negotiate(&session, &id, &StreamOptions::default()).await?;
+///
+/// let options = DownloadOptions {
+///     cookie: stream_session.cookie.clone(),
+///     video_id: Some(id),
+///     ..Default::default()
+/// };
+/// let mut download = download::to_file(&session, &stream_session.content_uri, "sm9.mp4", options);
+/// let mut heartbeat = stream_session.heartbeat.take();
+/// loop {
+///     let event = match &mut heartbeat {
+///         Some(heartbeat) => tokio::select! {
+///             event = download.next() => event,
+///             result = heartbeat.keep_alive(&session) => { result?; unreachable!() }
+///         },
+///         None => download.next().await,
+///     };
+///     match event.transpose()? {
+///         Some(DownloadEvent::Started { has_separate_audio: true, .. }) => {
+///             println!("the audio is written to {}", download::audio_path("sm9.mp4").display())
+///         }
+///         Some(DownloadEvent::SegmentDownloaded { index, total_segments, .. }) => {
+///             println!("{}/{}", index + 1, total_segments)
+///         }
+///         Some(_) => {}
+///         None => break,
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// An interrupted download is resumed even though the URL differs for each session:
+/// ```
+/// use futures::StreamExt;
+/// use niconico::{video::download::{self, DownloadEvent, DownloadOptions}, *};
+/// use std::{collections::HashMap, io::Write, sync::{Arc, Mutex}};
+/// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// // a stand-in for the HLS server
+/// let mut listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+/// let base = format!("http://{}", listener.local_addr()?);
+/// let mut files: HashMap<&str, &[u8]> = HashMap::new();
+/// files.insert("/master.m3u8", b"#EXTM3U
+/// #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"main\",DEFAULT=YES,URI=\"audio.m3u8\"
+/// #EXT-X-STREAM-INF:BANDWIDTH=2000000,RESOLUTION=1280x720,AUDIO=\"aac\"
+/// video.m3u8
+/// ");
+/// files.insert("/video.m3u8", b"#EXTM3U\n#EXTINF:6.0,\nv0.ts\n#EXTINF:6.0,\nv1.ts\n#EXTINF:6.0,\nv2.ts\n#EXT-X-ENDLIST\n");
+/// files.insert("/audio.m3u8", b"#EXTM3U
+/// #EXT-X-MAP:URI=\"init.mp4\"
+/// #EXT-X-KEY:METHOD=AES-128,URI=\"key\"
+/// #EXT-X-MEDIA-SEQUENCE:5
+/// #EXTINF:6.0,
+/// a5.m4s
+/// #EXT-X-ENDLIST
+/// ");
+/// files.insert("/v0.ts", b"v0");
+/// files.insert("/v1.ts", b"v1");
+/// files.insert("/v2.ts", b"v2");
+/// files.insert("/init.mp4", b"init");
+/// files.insert("/key", b"\x2b\x7e\x15\x16\x28\xae\xd2\xa6\xab\xf7\x15\x88\x09\xcf\x4f\x3c");
+/// // `hello` encrypted with the key and the media sequence number as the IV
+/// files.insert("/a5.m4s", b"\x19\x9e\x83\x18\x1c\xfc\xe0\x3c\xf4\xb4\xc8\xc9\x1a\xfd\x82\xf6");
+/// let requests = Arc::new(Mutex::new(Vec::new()));
+/// let server = {
+///     let requests = requests.clone();
+///     async move {
+///         loop {
+///             let (mut socket, _) = listener.accept().await.unwrap();
+///             let mut request = Vec::new();
+///             let mut buf = [0; 1024];
+///             while !request.ends_with(b"\r\n\r\n") {
+///                 let len = socket.read(&mut buf).await.unwrap();
+///                 request.extend_from_slice(&buf[..len]);
+///             }
+///             let request = String::from_utf8(request).unwrap();
+///             let path = request.split(' ').nth(1).unwrap().split('?').next().unwrap().to_owned();
+///             let body = files[path.as_str()];
+///             requests.lock().unwrap().push(path);
+///             let head = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+///             socket.write_all(head.as_bytes()).await.unwrap();
+///             socket.write_all(body).await.unwrap();
+///         }
+///     }
+/// };
+///
+/// let path = std::env::temp_dir().join(format!("niconico-download-{}.mp4", std::process::id()));
+/// let client = async {
+///     let session = Session::new("test", Language::English);
+///     let options = DownloadOptions {
+///         concurrency: 1,
+///         video_id: Some("sm9".parse()?),
+///         ..Default::default()
+///     };
+///
+///     // interrupted after two segments
+///     let url = format!("{}/master.m3u8?session=1", base);
+///     let mut first = download::to_file(&session, &url, &path, options.clone());
+///     for _ in 0..3 {
+///         first.next().await.unwrap()?;
+///     }
+///     drop(first);
+///     // a segment which was being written when interrupted
+///     std::fs::OpenOptions::new().append(true).open(&path)?.write_all(b"v2")?;
+///
+///     let url = format!("{}/master.m3u8?session=2", base);
+///     let second = download::to_file(&session, &url, &path, options);
+///     second.collect::<Vec<_>>().await.into_iter().collect::<anyhow::Result<Vec<_>>>()
+/// };
+/// let events = tokio::select! {
+///     _ = server => unreachable!(),
+///     events = client => events?,
+/// };
+///
+/// assert_eq!(
+///     events[0],
+///     DownloadEvent::Started { total_segments: 5, completed_segments: 2, has_separate_audio: true }
+/// );
+/// assert_eq!(events.last(), Some(&DownloadEvent::Finished { bytes: 15 }));
+/// assert_eq!(std::fs::read(&path)?, b"v0v1v2");
+/// assert_eq!(std::fs::read(download::audio_path(&path))?, b"inithello");
+/// let requests = requests.lock().unwrap();
+/// assert_eq!(requests.iter().filter(|path| *path == "/v0.ts").count(), 1);
+/// assert_eq!(requests.iter().filter(|path| *path == "/v1.ts").count(), 1);
+/// # std::fs::remove_file(&path)?;
+/// # std::fs::remove_file(download::audio_path(&path))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_file<'a>(
+    session: &'a Session,
+    url: &str,
+    path: impl AsRef<Path>,
+    options: DownloadOptions,
+) -> DownloadStream<'a> {
+    download(
+        session,
+        url.to_owned(),
+        Output::File(path.as_ref().to_owned()),
+        options,
+    )
+}
+/// Downloads the HLS content at `url` and writes it to `writer`.
+/// If the audio is in a separate rendition, it is written to `audio_writer`,
+/// or not downloaded at all if `audio_writer` is `None`.
+/// Unlike [`to_file`], the download cannot be resumed.
+pub fn to_writer<'a, W>(
+    session: &'a Session,
+    url: &str,
+    writer: W,
+    audio_writer: Option<W>,
+    options: DownloadOptions,
+) -> DownloadStream<'a>
+where
+    W: AsyncWrite + Unpin + 'a,
+{
+    download(
+        session,
+        url.to_owned(),
+        Output::Writer {
+            video: Box::new(writer),
+            audio: audio_writer.map(|w| Box::new(w) as Box<dyn AsyncWrite + Unpin + 'a>),
+        },
+        options,
+    )
+}
+/// Returns the path [`to_file`] writes the separate audio to, such as `sm9.audio.mp4` for `sm9.mp4`.
+///
+/// # Examples
+/// ```
+/// # use niconico::video::download::audio_path;
+/// # use std::path::Path;
+/// assert_eq!(audio_path("videos/sm9.mp4"), Path::new("videos/sm9.audio.mp4"));
+/// assert_eq!(audio_path("sm9"), Path::new("sm9.audio"));
+/// ```
+pub fn audio_path(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    let mut name = path.file_stem().unwrap_or_default().to_owned();
+    name.push(".audio");
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    path.with_file_name(name)
+}
+
+enum Output<'a> {
+    File(PathBuf),
+    Writer {
+        video: Box<dyn AsyncWrite + Unpin + 'a>,
+        audio: Option<Box<dyn AsyncWrite + Unpin + 'a>>,
+    },
+}
+
+enum State<'a> {
+    Init { url: String, output: Output<'a> },
+    Running(Box<Running<'a>>),
+    Done,
+}
+/// The decrypted segments, and whether each of them is of the audio.
+type SegmentStream<'a> = Pin<Box<dyn Stream<Item = Result<(bool, Vec<u8>)>> + 'a>>;
+struct Running<'a> {
+    segments: SegmentStream<'a>,
+    writer: Box<dyn AsyncWrite + Unpin + 'a>,
+    audio_writer: Option<Box<dyn AsyncWrite + Unpin + 'a>>,
+    state_path: Option<PathBuf>,
+    progress: Progress,
+    has_separate_audio: bool,
+}
+
+/// The progress saved in the state file.
+#[derive(Debug, Clone)]
+struct Progress {
+    /// The video ID and the chosen variant, such as `sm9 2000000 1280x720`.
+    /// `None` if the download is not resumed.
+    content: Option<String>,
+    total_segments: usize,
+    completed_segments: usize,
+    bytes_written: u64,
+    audio_bytes_written: u64,
+}
+impl Progress {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "content": self.content,
+            "totalSegments": self.total_segments,
+            "completedSegments": self.completed_segments,
+            "bytesWritten": self.bytes_written,
+            "audioBytesWritten": self.audio_bytes_written,
+        })
+    }
+    fn from_json(json: &serde_json::Value) -> Option<Progress> {
+        Some(Progress {
+            content: Some(json["content"].as_str()?.to_owned()),
+            total_segments: json["totalSegments"].as_u64()? as usize,
+            completed_segments: json["completedSegments"].as_u64()? as usize,
+            bytes_written: json["bytesWritten"].as_u64()?,
+            audio_bytes_written: json["audioBytesWritten"].as_u64()?,
+        })
+    }
+}
+
+fn download<'a>(
+    session: &'a Session,
+    url: String,
+    output: Output<'a>,
+    options: DownloadOptions,
+) -> DownloadStream<'a> {
+    let options = Arc::new(options);
+    Box::pin(stream::unfold(State::Init { url, output }, move |state| {
+        let options = options.clone();
+        async move {
+            match state {
+                State::Init { url, output } => match start(session, &url, output, options).await {
+                    Ok(running) => {
+                        let event = DownloadEvent::Started {
+                            total_segments: running.progress.total_segments,
+                            completed_segments: running.progress.completed_segments,
+                            has_separate_audio: running.has_separate_audio,
+                        };
+                        Some((Ok(event), State::Running(Box::new(running))))
+                    }
+                    Err(e) => Some((Err(e), State::Done)),
+                },
+                State::Running(mut running) => match running.next().await {
+                    Ok(Some(event)) => Some((Ok(event), State::Running(running))),
+                    Ok(None) => match running.finish().await {
+                        Ok(event) => Some((Ok(event), State::Done)),
+                        Err(e) => Some((Err(e), State::Done)),
+                    },
+                    Err(e) => Some((Err(e), State::Done)),
+                },
+                State::Done => None,
+            }
+        }
+    }))
+}
+
+/// Fetches the playlists and the keys, and opens the outputs.
+async fn start<'a>(
+    session: &'a Session,
+    url: &str,
+    output: Output<'a>,
+    options: Arc<DownloadOptions>,
+) -> Result<Running<'a>> {
+    let Content {
+        variant,
+        video,
+        audio,
+    } = fetch_content(session, url, &options).await?;
+    let has_separate_audio = audio.is_some();
+    let audio = match &output {
+        Output::Writer { audio: None, .. } => Vec::new(),
+        _ => audio.unwrap_or_default(),
+    };
+    let segments: Vec<_> = video
+        .into_iter()
+        .map(|segment| (false, segment))
+        .chain(audio.into_iter().map(|segment| (true, segment)))
+        .collect();
+
+    let mut keys = HashMap::new();
+    for (_, segment) in &segments {
+        if let Some(key) = &segment.key {
+            if !keys.contains_key(&key.uri) {
+                let bytes = fetch_bytes(session, &key.uri, &options).await?;
+                ensure!(
+                    bytes.len() == 16,
+                    anyhow!("the key is not 16 bytes: `{}`", key.uri)
+                        .context(Error::InvalidResponse)
+                );
+                keys.insert(key.uri.clone(), bytes);
+            }
+        }
+    }
+    let keys = Arc::new(keys);
+
+    let mut progress = Progress {
+        content: options
+            .video_id
+            .as_ref()
+            .map(|id| format!("{} {}", id, variant).trim_end().to_owned()),
+        total_segments: segments.len(),
+        completed_segments: 0,
+        bytes_written: 0,
+        audio_bytes_written: 0,
+    };
+    let (writer, audio_writer, state_path): (Box<dyn AsyncWrite + Unpin + 'a>, _, _) = match output
+    {
+        Output::Writer { video, audio } => (video, audio, None),
+        Output::File(path) => {
+            let mut state_path = path.clone().into_os_string();
+            state_path.push(".download.json");
+            let state_path = PathBuf::from(state_path);
+
+            // resume only if the previous download is of the same video and variant
+            if progress.content.is_some() {
+                if let Ok(state) = tokio::fs::read_to_string(&state_path).await {
+                    if let Some(saved) = serde_json::from_str(&state)
+                        .ok()
+                        .as_ref()
+                        .and_then(Progress::from_json)
+                    {
+                        if saved.content == progress.content
+                            && saved.total_segments == progress.total_segments
+                        {
+                            progress = saved;
+                        }
+                    }
+                }
+            }
+
+            let file = open(&path, progress.bytes_written).await?;
+            let audio_file: Option<Box<dyn AsyncWrite + Unpin + 'a>> = if has_separate_audio {
+                Some(Box::new(
+                    open(&audio_path(&path), progress.audio_bytes_written).await?,
+                ))
+            } else {
+                None
+            };
+            (
+                Box::new(file),
+                audio_file,
+                progress.content.as_ref().map(|_| state_path),
+            )
+        }
+    };
+
+    let concurrency = options.concurrency.max(1);
+    let segments = stream::iter(segments.into_iter().skip(progress.completed_segments))
+        .map(move |(is_audio, segment)| {
+            let keys = keys.clone();
+            let options = options.clone();
+            async move {
+                let mut bytes = fetch_bytes(session, &segment.uri, &options).await?;
+                if let Some(key) = &segment.key {
+                    bytes = decrypt(bytes, &keys[&key.uri], key.iv, segment.sequence)
+                        .with_context(|| format!("cannot decrypt `{}`", segment.uri))?;
+                }
+                Ok((is_audio, bytes))
+            }
+        })
+        .buffered(concurrency);
+
+    Ok(Running {
+        segments: Box::pin(segments),
+        writer,
+        audio_writer,
+        state_path,
+        progress,
+        has_separate_audio,
+    })
+}
+/// Opens the file, discarding the bytes after `len`, which are not recorded as written.
+async fn open(path: &Path, len: u64) -> Result<tokio::fs::File> {
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .await
+        .with_context(|| format!("cannot open `{}`", path.display()))?;
+    file.set_len(len)
+        .await
+        .with_context(|| format!("cannot truncate `{}`", path.display()))?;
+    file.seek(SeekFrom::End(0))
+        .await
+        .with_context(|| format!("cannot seek `{}`", path.display()))?;
+    Ok(file)
+}
+impl<'a> Running<'a> {
+    /// Writes the next segment. Returns `None` if all the segments are written.
+    async fn next(&mut self) -> Result<Option<DownloadEvent>> {
+        let (is_audio, bytes) = match self.segments.next().await {
+            Some(segment) => segment?,
+            None => return Ok(None),
+        };
+        // the segments of the audio are fetched only if there is an output for them
+        let writer = match &mut self.audio_writer {
+            Some(audio_writer) if is_audio => audio_writer,
+            _ => &mut self.writer,
+        };
+        writer
+            .write_all(&bytes)
+            .await
+            .context("cannot write a segment")?;
+        writer.flush().await.context("cannot write a segment")?;
+
+        let index = self.progress.completed_segments;
+        self.progress.completed_segments += 1;
+        if is_audio {
+            self.progress.audio_bytes_written += bytes.len() as u64;
+        } else {
+            self.progress.bytes_written += bytes.len() as u64;
+        }
+        if let Some(state_path) = &self.state_path {
+            tokio::fs::write(state_path, self.progress.to_json().to_string())
+                .await
+                .with_context(|| format!("cannot write `{}`", state_path.display()))?;
+        }
+
+        Ok(Some(DownloadEvent::SegmentDownloaded {
+            index,
+            bytes: bytes.len() as u64,
+            total_segments: self.progress.total_segments,
+        }))
+    }
+    /// Removes the state file.
+    async fn finish(&mut self) -> Result<DownloadEvent> {
+        if let Some(state_path) = &self.state_path {
+            if tokio::fs::metadata(state_path).await.is_ok() {
+                tokio::fs::remove_file(state_path)
+                    .await
+                    .with_context(|| format!("cannot remove `{}`", state_path.display()))?;
+            }
+        }
+        Ok(DownloadEvent::Finished {
+            bytes: self.progress.bytes_written + self.progress.audio_bytes_written,
+        })
+    }
+}
+
+/// The segments of the chosen variant.
+struct Content {
+    /// Identifies the variant by its bandwidth and resolution, such as `2000000 1280x720`.
+    /// Empty if the URL points to a media playlist.
+    variant: String,
+    video: Vec<Segment>,
+    /// The segments of the separate audio rendition. `None` if the audio is contained in the video.
+    audio: Option<Vec<Segment>>,
+}
+
+/// Fetches the playlists of the chosen variant and its audio rendition.
+async fn fetch_content(session: &Session, url: &str, options: &DownloadOptions) -> Result<Content> {
+    let mut url = url.to_owned();
+    let mut variant_id = String::new();
+    let mut audio = None;
+    // a master playlist may point to another master playlist, but not endlessly
+    for _ in 0..4 {
+        match fetch_playlist(session, &url, options).await? {
+            Playlist::Media(video) => {
+                return Ok(Content {
+                    variant: variant_id,
+                    video,
+                    audio,
+                })
+            }
+            Playlist::Master(variants) => {
+                let variant = match options.quality {
+                    super::stream::QualityPreference::Highest => {
+                        variants.into_iter().max_by_key(|v| v.bandwidth)
+                    }
+                    super::stream::QualityPreference::Lowest => {
+                        variants.into_iter().min_by_key(|v| v.bandwidth)
+                    }
+                };
+                let variant = variant
+                    .context("the master playlist has no variants")
+                    .context(Error::InvalidResponse)?;
+                variant_id = match &variant.resolution {
+                    Some(resolution) => format!("{} {}", variant.bandwidth, resolution),
+                    None => variant.bandwidth.to_string(),
+                };
+                if let Some(audio_url) = &variant.audio {
+                    match fetch_playlist(session, audio_url, options).await? {
+                        Playlist::Media(segments) => audio = Some(segments),
+                        Playlist::Master(_) => bail!(anyhow!(
+                            "the audio rendition is a master playlist: `{}`",
+                            audio_url
+                        )
+                        .context(Error::InvalidResponse)),
+                    }
+                }
+                url = variant.uri;
+            }
+        }
+    }
+    bail!(anyhow!("too many nested master playlists").context(Error::InvalidResponse))
+}
+async fn fetch_playlist(
+    session: &Session,
+    url: &str,
+    options: &DownloadOptions,
+) -> Result<Playlist> {
+    let text = String::from_utf8(fetch_bytes(session, url, options).await?)
+        .context(Error::InvalidResponse)?;
+    playlist::parse(&text, url)
+}
+async fn fetch_bytes(session: &Session, url: &str, options: &DownloadOptions) -> Result<Vec<u8>> {
+    let mut request = session.get(
+        url,
+        RequestOptions {
+            cookie_user_session: false,
+            ..Default::default()
+        },
+    );
+    if let Some(cookie) = &options.cookie {
+        request = request.header("Cookie", cookie);
+    }
+    let bytes = request
+        .send()
+        .await
+        .and_then(|res| res.error_for_status())
+        .with_context(|| format!("cannot fetch from `{}`", url))
+        .context(Error::InvalidResponse)?
+        .bytes()
+        .await
+        .with_context(|| format!("cannot fetch from `{}`", url))
+        .context(Error::InvalidResponse)?;
+    Ok(bytes.to_vec())
+}
+/// Decrypts a segment encrypted with AES-128 (`#EXT-X-KEY:METHOD=AES-128`).
+/// If `iv` is `None`, the media sequence number of the segment is used as the IV.
+///
+/// # Examples
+/// ```
+/// # use niconico::video::download::decrypt;
+/// let hex = |s: &str| -> Vec<u8> {
+///     (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+/// };
+/// let key = hex("2b7e151628aed2a6abf7158809cf4f3c");
+///
+/// let mut iv = [0; 16];
+/// iv.copy_from_slice(&hex("000102030405060708090a0b0c0d0e0f"));
+/// let ciphertext = hex("7649abac8119b246cee98e9b12e9197d8964e0b149c10b7b682e6e39aaeb731c");
+/// assert_eq!(decrypt(ciphertext, &key, Some(iv), 0)?, hex("6bc1bee22e409f96e93d7e117393172a"));
+///
+/// let ciphertext = hex("199e83181cfce03cf4b4c8c91afd82f6");
+/// assert_eq!(decrypt(ciphertext.clone(), &key, None, 5)?, b"hello");
+/// assert!(decrypt(ciphertext.clone(), &key, None, 6).is_err());
+/// assert!(decrypt(ciphertext, &key[..15], None, 5).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn decrypt(
+    mut bytes: Vec<u8>,
+    key: &[u8],
+    iv: Option<[u8; 16]>,
+    sequence: u64,
+) -> Result<Vec<u8>> {
+    use cbc::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+
+    ensure!(key.len() == 16, "the key is not 16 bytes");
+    let iv = iv.unwrap_or_else(|| (sequence as u128).to_be_bytes());
+    let len = cbc::Decryptor::<aes::Aes128>::new(key.into(), &iv.into())
+        .decrypt_padded_mut::<Pkcs7>(&mut bytes)
+        .map_err(|_| anyhow!("invalid padding"))
+        .context(Error::InvalidResponse)?
+        .len();
+    bytes.truncate(len);
+    Ok(bytes)
+}
@@ -0,0 +1,257 @@
+use crate::*;
+
+/// Represents an HLS playlist.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Playlist {
+    /// A master playlist, which lists the variants of the content.
+    Master(Vec<Variant>),
+    /// A media playlist, which lists the segments of a variant.
+    Media(Vec<Segment>),
+}
+/// Represents a variant listed in a master playlist.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Variant {
+    /// The absolute URL of the media playlist.
+    pub uri: String,
+    /// The peak bitrate of the variant in bits per second.
+    pub bandwidth: u64,
+    /// The resolution of the video, such as `1280x720`.
+    pub resolution: Option<String>,
+    /// The absolute URL of the media playlist of the separate audio rendition (`#EXT-X-MEDIA:TYPE=AUDIO`).
+    /// `None` if the audio is contained in the variant itself.
+    pub audio: Option<String>,
+}
+/// Represents a segment listed in a media playlist.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Segment {
+    /// The absolute URL of the segment.
+    pub uri: String,
+    /// The key to decrypt the segment. `None` if the segment is not encrypted.
+    pub key: Option<Key>,
+    /// The media sequence number of the segment.
+    pub sequence: u64,
+}
+/// Represents an AES-128 key to decrypt segments.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Key {
+    /// The absolute URL of the key.
+    pub uri: String,
+    /// The initialization vector. If `None`, the media sequence number is used.
+    pub iv: Option<[u8; 16]>,
+}
+
+/// Parses an HLS playlist. Relative URLs are resolved against `base_url`.
+///
+/// Initialization sections (`#EXT-X-MAP`) are returned as the first segment.
+/// If an audio group has several renditions, the default one is taken as [`Variant::audio`].
+///
+/// # Examples
+/// ```
+/// # use niconico::video::download::playlist::*;
+/// let master = "#EXTM3U\n\
+///     #EXT-X-STREAM-INF:BANDWIDTH=800000\n\
+///     low/playlist.m3u8\n\
+///     #EXT-X-STREAM-INF:BANDWIDTH=2000000,RESOLUTION=1280x720\n\
+///     high/playlist.m3u8\n";
+/// let playlist = parse(master, "https://example.com/master.m3u8?ht2=x")?;
+/// assert_eq!(
+///     playlist,
+///     Playlist::Master(vec![
+///         Variant { uri: "https://example.com/low/playlist.m3u8".to_owned(), bandwidth: 800000, resolution: None, audio: None },
+///         Variant {
+///             uri: "https://example.com/high/playlist.m3u8".to_owned(),
+///             bandwidth: 2000000,
+///             resolution: Some("1280x720".to_owned()),
+///             audio: None,
+///         },
+///     ])
+/// );
+///
+/// let master = "#EXTM3U\n\
+///     #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"low\",URI=\"audio/low.m3u8\"\n\
+///     #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"high\",DEFAULT=YES,URI=\"audio/high.m3u8\"\n\
+///     #EXT-X-STREAM-INF:BANDWIDTH=2000000,AUDIO=\"aac\"\n\
+///     video.m3u8\n";
+/// let variants = match parse(master, "https://example.com/master.m3u8")? {
+///     Playlist::Master(variants) => variants,
+///     _ => unreachable!(),
+/// };
+/// assert_eq!(variants[0].audio.as_deref(), Some("https://example.com/audio/high.m3u8"));
+///
+/// let media = "#EXTM3U\n\
+///     #EXT-X-MEDIA-SEQUENCE:5\n\
+///     #EXTINF:6.0,\n\
+///     1.ts\n\
+///     #EXT-X-KEY:METHOD=AES-128,URI=\"key\",IV=0x000102030405060708090a0b0c0d0e0f\n\
+///     #EXTINF:6.0,\n\
+///     2.ts\n\
+///     #EXT-X-ENDLIST\n";
+/// let segments = match parse(media, "https://example.com/high/playlist.m3u8")? {
+///     Playlist::Media(segments) => segments,
+///     _ => unreachable!(),
+/// };
+/// assert_eq!(segments.len(), 2);
+/// assert_eq!(segments[0].uri, "https://example.com/high/1.ts");
+/// assert_eq!(segments[0].key, None);
+/// assert_eq!(segments[1].sequence, 6);
+/// let key = segments[1].key.as_ref().unwrap();
+/// assert_eq!(key.uri, "https://example.com/high/key");
+/// assert_eq!(key.iv.unwrap()[15], 0x0f);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse(playlist: &str, base_url: &str) -> Result<Playlist> {
+    let base_url = reqwest::Url::parse(base_url).context("invalid base url")?;
+    let resolve = |uri: &str| -> Result<String> {
+        let url = base_url
+            .join(uri)
+            .with_context(|| format!("invalid uri: `{}`", uri))
+            .context(Error::InvalidResponse)?;
+        Ok(url.into())
+    };
+
+    let mut lines = playlist.lines().map(str::trim).filter(|l| !l.is_empty());
+    ensure!(
+        lines.next() == Some("#EXTM3U"),
+        anyhow!("not an HLS playlist").context(Error::InvalidResponse)
+    );
+
+    let mut variants = Vec::new();
+    // `(variant, audio group)`
+    let mut variant_groups = Vec::new();
+    // `(audio group, uri, is_default)`
+    let mut audio_renditions = Vec::new();
+    let mut segments = Vec::new();
+    let mut pending_variant = None;
+    let mut key = None;
+    let mut sequence = 0;
+    for line in lines {
+        if let Some(attrs) = line.strip_prefix("#EXT-X-STREAM-INF:") {
+            let bandwidth = attribute(attrs, "BANDWIDTH")
+                .and_then(|b| b.parse().ok())
+                .unwrap_or(0);
+            pending_variant = Some((
+                bandwidth,
+                attribute(attrs, "RESOLUTION").map(str::to_owned),
+                attribute(attrs, "AUDIO"),
+            ));
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-MEDIA:") {
+            // renditions without `URI` are contained in the variants
+            if let (Some("AUDIO"), Some(group), Some(uri)) = (
+                attribute(attrs, "TYPE"),
+                attribute(attrs, "GROUP-ID"),
+                attribute(attrs, "URI"),
+            ) {
+                let is_default = attribute(attrs, "DEFAULT") == Some("YES");
+                audio_renditions.push((group, resolve(uri)?, is_default));
+            }
+        } else if let Some(value) = line.strip_prefix("#EXT-X-MEDIA-SEQUENCE:") {
+            sequence = value.parse().context(Error::InvalidResponse)?;
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-KEY:") {
+            key = match attribute(attrs, "METHOD") {
+                Some("NONE") => None,
+                Some("AES-128") => Some(Key {
+                    uri: resolve(
+                        attribute(attrs, "URI")
+                            .context("`URI` of the key is missing")
+                            .context(Error::InvalidResponse)?,
+                    )?,
+                    iv: attribute(attrs, "IV").map(parse_iv).transpose()?,
+                }),
+                method => bail!(anyhow!("unsupported encryption method: {:?}", method)
+                    .context(Error::InvalidResponse)),
+            };
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-MAP:") {
+            let uri = attribute(attrs, "URI")
+                .context("`URI` of the map is missing")
+                .context(Error::InvalidResponse)?;
+            segments.push(Segment {
+                uri: resolve(uri)?,
+                key: key.clone(),
+                sequence,
+            });
+        } else if line.starts_with('#') {
+            // other tags are not needed to download
+        } else if let Some((bandwidth, resolution, audio_group)) = pending_variant.take() {
+            variant_groups.push((
+                Variant {
+                    uri: resolve(line)?,
+                    bandwidth,
+                    resolution,
+                    audio: None,
+                },
+                audio_group,
+            ));
+        } else {
+            segments.push(Segment {
+                uri: resolve(line)?,
+                key: key.clone(),
+                sequence,
+            });
+            sequence += 1;
+        }
+    }
+
+    // `#EXT-X-MEDIA` may follow the variants which refer to it
+    for (mut variant, audio_group) in variant_groups {
+        if let Some(audio_group) = audio_group {
+            let renditions: Vec<_> = audio_renditions
+                .iter()
+                .filter(|(group, _, _)| *group == audio_group)
+                .collect();
+            variant.audio = renditions
+                .iter()
+                .find(|(_, _, is_default)| *is_default)
+                .or_else(|| renditions.first())
+                .map(|(_, uri, _)| uri.clone());
+        }
+        variants.push(variant);
+    }
+
+    if variants.is_empty() {
+        Ok(Playlist::Media(segments))
+    } else {
+        Ok(Playlist::Master(variants))
+    }
+}
+
+/// Finds the value of the attribute in an attribute list such as `METHOD=AES-128,URI="..."`.
+fn attribute<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = attrs;
+    while !rest.is_empty() {
+        let eq = rest.find('=')?;
+        let key = rest[..eq].trim();
+        rest = &rest[eq + 1..];
+        let value = if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"')?;
+            rest = &quoted[end + 1..];
+            &quoted[..end]
+        } else {
+            let end = rest.find(',').unwrap_or(rest.len());
+            let value = &rest[..end];
+            rest = &rest[end..];
+            value
+        };
+        if key == name {
+            return Some(value);
+        }
+        rest = rest.trim_start_matches(',');
+    }
+    None
+}
+fn parse_iv(iv: &str) -> Result<[u8; 16]> {
+    let hex = iv
+        .strip_prefix("0x")
+        .or_else(|| iv.strip_prefix("0X"))
+        .unwrap_or(iv);
+    ensure!(
+        hex.len() == 32 && hex.is_ascii(),
+        anyhow!("invalid iv: `{}`", iv).context(Error::InvalidResponse)
+    );
+    let mut bytes = [0; 16];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .with_context(|| format!("invalid iv: `{}`", iv))
+            .context(Error::InvalidResponse)?;
+    }
+    Ok(bytes)
+}
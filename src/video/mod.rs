@@ -0,0 +1,124 @@
+use crate::*;
+
+pub mod comments;
+pub mod details;
+pub mod download;
+pub mod stream;
+pub mod summary;
+pub mod thumb_info;
+
+/// Represents the ID of a video, such as `sm9`.
+///
+/// # Examples
+/// ```
+/// # use niconico::*;
+/// let id: VideoId = "sm9".parse()?;
+/// assert_eq!(id.as_str(), "sm9");
+///
+/// let id: VideoId = "https://www.nicovideo.jp/watch/so123?ref=top".parse()?;
+/// assert_eq!(id.as_str(), "so123");
+///
+/// assert!("lv123".parse::<VideoId>().is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct VideoId(String);
+impl VideoId {
+    /// Returns the ID as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+    /// Gets the url of the watch page.
+    pub fn watch_url(&self) -> String {
+        format!("https://www.nicovideo.jp/watch/{}", self.0)
+    }
+    /// Fetches the details of this video.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use niconico::*;
+    /// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let session = Session::new(USER_AGENT, Language::Japanese);
+    /// let details = "sm9".parse::<VideoId>()?.fetch_details(&session).await?;
+    /// println!("{}: {:?}", details.title, details.duration);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_details(&self, session: &Session) -> Result<details::VideoDetails> {
+        details::VideoDetails::fetch(session, self).await
+    }
+}
+impl std::str::FromStr for VideoId {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // accepts watch urls such as `https://www.nicovideo.jp/watch/sm9` or `https://nico.ms/sm9`
+        let id = s
+            .split(['?', '#'].as_ref())
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default();
+
+        let is_valid = ["sm", "nm", "so"].iter().any(|prefix| {
+            id.starts_with(prefix)
+                && id.len() > prefix.len()
+                && id[prefix.len()..].bytes().all(|b| b.is_ascii_digit())
+        });
+        ensure!(
+            is_valid,
+            anyhow!("`{}` is not a video id", s).context(Error::InvalidId)
+        );
+
+        Ok(VideoId(id.to_owned()))
+    }
+}
+impl std::fmt::Display for VideoId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Fetches the information of a video from the legacy `getthumbinfo` API.
+/// Unlike [`VideoId::fetch_details`], this works without login.
+///
+/// # Errors
+/// - `Error::VideoDeleted` if the video has been deleted.
+/// - `Error::VideoNotFound` if the video does not exist.
+///
+/// # Examples
+/// ```no_run
+/// # use niconico::*;
+/// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let session = Session::new(USER_AGENT, Language::Japanese);
+/// let thumb_info = video::thumbinfo(&session, &"sm9".parse()?).await?;
+/// println!("{} ({} views)", thumb_info.title, thumb_info.view_count);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn thumbinfo(session: &Session, id: &VideoId) -> Result<thumb_info::ThumbInfo> {
+    thumb_info::ThumbInfo::fetch(session, id).await
+}
+
+/// Fetches the watch page and extracts the data embedded in it.
+pub(crate) async fn fetch_watch_data(session: &Session, id: &VideoId) -> Result<serde_json::Value> {
+    html_extractor::html_extractor! {
+        WatchPage {
+            js_initial_watch_data: String = (attr["data-api-data"] of "#js-initial-watch-data")
+        }
+    }
+
+    let url = format!("{}/watch/{}", session.endpoints().www, id);
+    let watch_page: WatchPage = session
+        .get_data(&url, None)
+        .await
+        .context("cannot fetch the watch page")?;
+    let data =
+        serde_json::from_str(&watch_page.js_initial_watch_data).context(Error::InvalidResponse)?;
+    Ok(data)
+}
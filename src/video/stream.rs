@@ -0,0 +1,366 @@
+use crate::*;
+use std::time::Duration;
+
+/// Negotiates a delivery session of the video and returns the URI of the content.
+///
+/// Videos delivered by domand are always delivered over HLS, ignoring `options.protocol`;
+/// check [`StreamSession::protocol`] for the protocol actually used.
+/// Otherwise, the session is negotiated with DMC and `options.protocol` is honored.
+///
+/// # Examples
+/// ```no_run
+/// # use niconico::*;
+/// use video::stream::{self, StreamOptions};
+/// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// # let mut session = Session::new(USER_AGENT, Language::Japanese);
+/// # session.set_cookie_user_session(&std::env::var("NICO_SID")?);
+/// let mut stream_session =
+///     stream::negotiate(&session, &"sm9".parse()?, &StreamOptions::default()).await?;
+/// println!("{}", stream_session.content_uri);
+///
+/// // keep the session alive while using the content
+/// if let Some(heartbeat) = stream_session.heartbeat.as_mut() {
+///     heartbeat.keep_alive(&session).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn negotiate(
+    session: &Session,
+    id: &VideoId,
+    options: &StreamOptions,
+) -> Result<StreamSession> {
+    let data = super::fetch_watch_data(session, id).await?;
+
+    let media = &data["media"];
+    if !media["domand"].is_null() {
+        negotiate_domand(session, &data, options).await
+    } else if !media["delivery"].is_null() {
+        negotiate_dmc(session, &media["delivery"]["movie"], options).await
+    } else {
+        bail!(anyhow!("the video has no delivery information").context(Error::InvalidResponse))
+    }
+}
+
+/// Represents options for [`negotiate`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct StreamOptions {
+    /// Which video quality to use.
+    pub video_quality: QualityPreference,
+    /// Which audio quality to use.
+    pub audio_quality: QualityPreference,
+    /// The protocol to deliver the content. Ignored for videos delivered by domand.
+    pub protocol: Protocol,
+}
+impl Default for StreamOptions {
+    fn default() -> Self {
+        StreamOptions {
+            video_quality: QualityPreference::Highest,
+            audio_quality: QualityPreference::Highest,
+            protocol: Protocol::Hls,
+        }
+    }
+}
+/// Represents which quality to choose among the available ones.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum QualityPreference {
+    /// The quality with the highest bitrate.
+    Highest,
+    /// The quality with the lowest bitrate.
+    Lowest,
+}
+/// Represents a protocol to deliver the content.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Protocol {
+    /// HTTP Live Streaming. The content URI points to a master playlist.
+    Hls,
+    /// Progressive download over HTTP. The content URI points to the whole file.
+    Http,
+}
+
+/// Represents a negotiated delivery session.
+#[derive(Debug, Clone)]
+pub struct StreamSession {
+    /// The URI of the content.
+    pub content_uri: String,
+    /// The protocol the content is delivered with.
+    pub protocol: Protocol,
+    /// The ID of the chosen video quality, such as `archive_h264_720p`.
+    pub video_quality: String,
+    /// The ID of the chosen audio quality, such as `archive_aac_192kbps`.
+    pub audio_quality: String,
+    /// The value of header `Cookie` required to fetch the content, if any.
+    pub cookie: Option<String>,
+    /// The heartbeat which keeps the session alive. `None` if the session does not expire.
+    pub heartbeat: Option<Heartbeat>,
+}
+
+/// Keeps a DMC session alive. See [`negotiate`] for examples.
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    url: String,
+    session_data: serde_json::Value,
+    interval: Duration,
+}
+impl Heartbeat {
+    /// Returns how often heartbeats should be sent.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+    /// Sends a heartbeat once.
+    pub async fn beat(&mut self, session: &Session) -> Result<()> {
+        let body = serde_json::json!({ "session": self.session_data });
+        let json = session
+            .send_json(
+                session
+                    .post(&self.url, None)
+                    .header("Content-Type", "application/json")
+                    .body(body.to_string()),
+            )
+            .await
+            .context("cannot send a heartbeat")?;
+
+        let status = json_extract!(json, as_u64, ["meta"]["status"]);
+        ensure!(
+            status == 200,
+            anyhow!("heartbeat status: {}", status).context(Error::InvalidResponse)
+        );
+        self.session_data = json["data"]["session"].clone();
+        Ok(())
+    }
+    /// Sends heartbeats at the interval until an error occurs. Drop the future to stop.
+    pub async fn keep_alive(&mut self, session: &Session) -> Result<()> {
+        loop {
+            tokio::time::delay_for(self.interval).await;
+            self.beat(session).await?;
+        }
+    }
+}
+
+/// Represents a quality listed in the watch data.
+struct Quality {
+    id: String,
+    bitrate: u64,
+}
+fn choose_quality(qualities: Vec<Quality>, preference: QualityPreference) -> Result<String> {
+    let quality = match preference {
+        QualityPreference::Highest => qualities.into_iter().max_by_key(|q| q.bitrate),
+        QualityPreference::Lowest => qualities.into_iter().min_by_key(|q| q.bitrate),
+    };
+    Ok(quality
+        .context("no quality is available")
+        .context(Error::InvalidResponse)?
+        .id)
+}
+
+async fn negotiate_dmc(
+    session: &Session,
+    movie: &serde_json::Value,
+    options: &StreamOptions,
+) -> Result<StreamSession> {
+    let mut videos = Vec::new();
+    for video in json_extract!(movie, as_array, ["videos"]) {
+        if json_extract!(video, as_bool, ["isAvailable"]) {
+            videos.push(Quality {
+                id: json_extract!(video, as_string, ["id"]),
+                bitrate: json_extract!(video, as_u64, ["metadata"]["bitrate"]),
+            });
+        }
+    }
+    let mut audios = Vec::new();
+    for audio in json_extract!(movie, as_array, ["audios"]) {
+        if json_extract!(audio, as_bool, ["isAvailable"]) {
+            audios.push(Quality {
+                id: json_extract!(audio, as_string, ["id"]),
+                bitrate: json_extract!(audio, as_u64, ["metadata"]["bitrate"]),
+            });
+        }
+    }
+    let video_quality = choose_quality(videos, options.video_quality)?;
+    let audio_quality = choose_quality(audios, options.audio_quality)?;
+
+    let dmc = &movie["session"];
+    let url = json_extract!(dmc, as_string, ["urls"][0]["url"]);
+    let heartbeat_lifetime = json_extract!(dmc, as_u64, ["heartbeatLifetime"]);
+    let (protocol_name, parameters) = match options.protocol {
+        Protocol::Hls => (
+            "hls",
+            serde_json::json!({
+                "hls_parameters": {
+                    "use_well_known_port": "yes",
+                    "use_ssl": "yes",
+                    "transfer_preset": "",
+                    "segment_duration": 6000,
+                }
+            }),
+        ),
+        Protocol::Http => (
+            "http",
+            serde_json::json!({
+                "http_output_download_parameters": {
+                    "use_well_known_port": "yes",
+                    "use_ssl": "yes",
+                    "transfer_preset": "",
+                }
+            }),
+        ),
+    };
+    let body = serde_json::json!({
+        "session": {
+            "recipe_id": json_extract!(dmc, as_str, ["recipeId"]),
+            "content_id": json_extract!(dmc, as_str, ["contentId"]),
+            "content_type": "movie",
+            "content_src_id_sets": [{
+                "content_src_ids": [{
+                    "src_id_to_mux": {
+                        "video_src_ids": [video_quality],
+                        "audio_src_ids": [audio_quality],
+                    }
+                }]
+            }],
+            "timing_constraint": "unlimited",
+            "keep_method": { "heartbeat": { "lifetime": heartbeat_lifetime } },
+            "protocol": {
+                "name": "http",
+                "parameters": { "http_parameters": { "parameters": parameters } },
+            },
+            "content_uri": "",
+            "session_operation_auth": {
+                "session_operation_auth_by_signature": {
+                    "token": json_extract!(dmc, as_str, ["token"]),
+                    "signature": json_extract!(dmc, as_str, ["signature"]),
+                }
+            },
+            "content_auth": {
+                "auth_type": json_extract!(dmc, as_str, ["authTypes"][protocol_name]),
+                "content_key_timeout": json_extract!(dmc, as_u64, ["contentKeyTimeout"]),
+                "service_id": "nicovideo",
+                "service_user_id": json_extract!(dmc, as_str, ["serviceUserId"]),
+            },
+            "client_info": { "player_id": json_extract!(dmc, as_str, ["playerId"]) },
+            "priority": json_extract!(dmc, as_f64, ["priority"]),
+        }
+    });
+
+    let json = session
+        .send_json(
+            session
+                .post(&format!("{}?_format=json", url), None)
+                .header("Content-Type", "application/json")
+                .body(body.to_string()),
+        )
+        .await
+        .context("cannot create a DMC session")?;
+    let status = json_extract!(json, as_u64, ["meta"]["status"]);
+    ensure!(
+        status == 201,
+        anyhow!("DMC session status: {}", status).context(Error::InvalidResponse)
+    );
+
+    let session_data = json["data"]["session"].clone();
+    let session_id = json_extract!(session_data, as_str, ["id"]);
+    Ok(StreamSession {
+        content_uri: json_extract!(session_data, as_string, ["content_uri"]),
+        protocol: options.protocol,
+        video_quality,
+        audio_quality,
+        cookie: None,
+        heartbeat: Some(Heartbeat {
+            url: format!("{}/{}?_format=json&_method=PUT", url, session_id),
+            session_data,
+            interval: Duration::from_millis(heartbeat_lifetime / 3),
+        }),
+    })
+}
+
+async fn negotiate_domand(
+    session: &Session,
+    data: &serde_json::Value,
+    options: &StreamOptions,
+) -> Result<StreamSession> {
+    let domand = &data["media"]["domand"];
+
+    let mut videos = Vec::new();
+    for video in json_extract!(domand, as_array, ["videos"]) {
+        if json_extract!(video, as_bool, ["isAvailable"]) {
+            videos.push(Quality {
+                id: json_extract!(video, as_string, ["id"]),
+                bitrate: json_extract!(video, as_u64, ["bitRate"]),
+            });
+        }
+    }
+    let mut audios = Vec::new();
+    for audio in json_extract!(domand, as_array, ["audios"]) {
+        if json_extract!(audio, as_bool, ["isAvailable"]) {
+            audios.push(Quality {
+                id: json_extract!(audio, as_string, ["id"]),
+                bitrate: json_extract!(audio, as_u64, ["bitRate"]),
+            });
+        }
+    }
+    let video_quality = choose_quality(videos, options.video_quality)?;
+    let audio_quality = choose_quality(audios, options.audio_quality)?;
+
+    let url = format!(
+        "{}/v1/watch/{}/access-rights/hls?actionTrackId={}",
+        session.endpoints().nvapi,
+        json_extract!(data, as_str, ["video"]["id"]),
+        json_extract!(data, as_str, ["client"]["watchTrackId"])
+    );
+    let body = serde_json::json!({ "outputs": [[video_quality, audio_quality]] });
+    let response = session
+        .post(
+            &url,
+            RequestOptions {
+                header_x_frontend_id: true,
+                header_x_request_with: true,
+                ..Default::default()
+            },
+        )
+        .header("X-Frontend-Version", 0)
+        .header(
+            "X-Access-Right-Key",
+            json_extract!(domand, as_str, ["accessRightKey"]),
+        )
+        .header("Content-Type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .context("cannot obtain the access right")
+        .context(Error::InvalidResponse)?;
+
+    // the content can be fetched only with this cookie
+    let cookie = response
+        .cookies()
+        .find(|c| c.name() == "domand_bid")
+        .map(|c| format!("domand_bid={}", c.value()));
+    let json: serde_json::Value = serde_json::from_str(
+        &response
+            .text()
+            .await
+            .context("cannot obtain the access right")
+            .context(Error::InvalidResponse)?,
+    )
+    .context(Error::InvalidResponse)?;
+
+    let status = json_extract!(json, as_u64, ["meta"]["status"]);
+    match status {
+        200 | 201 => (),
+        401 => bail!(Error::LoginRequired),
+        _ => {
+            let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+            bail!(anyhow!("{} {}", status, error_code).context(Error::InvalidResponse));
+        }
+    }
+
+    Ok(StreamSession {
+        content_uri: json_extract!(json, as_string, ["data"]["contentUrl"]),
+        protocol: Protocol::Hls,
+        video_quality,
+        audio_quality,
+        cookie,
+        heartbeat: None,
+    })
+}
@@ -0,0 +1,90 @@
+use super::details::VideoOwner;
+use crate::*;
+use std::time::Duration;
+
+/// Represents a summary of a video, which is shown in lists such as mylists.
+#[derive(Debug, Clone)]
+pub struct VideoSummary {
+    /// The ID of this video.
+    pub id: VideoId,
+    /// The title of this video.
+    pub title: String,
+    /// The beginning of the description of this video, without HTML.
+    pub short_description: String,
+    /// The length of this video.
+    pub duration: Duration,
+    /// The date when this video was uploaded.
+    pub registered_at: DateTime<FixedOffset>,
+    /// The number of times this video has been viewed.
+    pub view_count: u64,
+    /// The number of comments on this video.
+    pub comment_count: u64,
+    /// The number of mylists this video is registered to.
+    pub mylist_count: u64,
+    /// The number of likes (いいね！) of this video.
+    pub like_count: u64,
+    /// The bodies of the latest comments.
+    pub latest_comment_summary: String,
+    /// The URL of the thumbnail.
+    pub thumbnail_url: String,
+    /// The uploader of this video. `None` if the uploader is hidden or has left niconico.
+    pub owner: Option<VideoOwner>,
+    /// Whether this video is uploaded by a channel.
+    pub is_channel_video: bool,
+    /// Whether this video requires payment to watch.
+    pub is_payment_required: bool,
+}
+impl VideoSummary {
+    /// Parses a video object of nvapi. (`"type": "essential"`)
+    pub(crate) fn from_json(video: &serde_json::Value) -> Result<VideoSummary> {
+        let owner = &video["owner"];
+        let owner = match (
+            owner["ownerType"].as_str(),
+            owner["id"].as_str(),
+            owner["name"].as_str(),
+        ) {
+            (Some("user"), Some(id), Some(name)) => Some(VideoOwner::User {
+                user: User::UserId(id.parse().context(Error::InvalidResponse)?),
+                nickname: name.to_owned(),
+                icon_url: json_extract!(owner, as_string, ["iconUrl"]),
+            }),
+            (Some("channel"), Some(id), Some(name)) => Some(VideoOwner::Channel {
+                id: id
+                    .trim_start_matches("ch")
+                    .parse()
+                    .context(Error::InvalidResponse)?,
+                name: name.to_owned(),
+                icon_url: json_extract!(owner, as_string, ["iconUrl"]),
+            }),
+            _ => None,
+        };
+
+        Ok(VideoSummary {
+            id: json_extract!(video, as_str, ["id"]).parse()?,
+            title: json_extract!(video, as_string, ["title"]),
+            short_description: json_extract_optional!(video, as_string, ["shortDescription"])
+                .unwrap_or_default(),
+            duration: Duration::from_secs(json_extract!(video, as_u64, ["duration"])),
+            registered_at: DateTime::<FixedOffset>::parse_from_rfc3339(json_extract!(
+                video,
+                as_str,
+                ["registeredAt"]
+            ))
+            .context(Error::InvalidResponse)?,
+            view_count: json_extract!(video, as_u64, ["count"]["view"]),
+            comment_count: json_extract!(video, as_u64, ["count"]["comment"]),
+            mylist_count: json_extract!(video, as_u64, ["count"]["mylist"]),
+            like_count: json_extract!(video, as_u64, ["count"]["like"]),
+            latest_comment_summary: json_extract_optional!(
+                video,
+                as_string,
+                ["latestCommentSummary"]
+            )
+            .unwrap_or_default(),
+            thumbnail_url: json_extract!(video, as_string, ["thumbnail"]["url"]),
+            owner,
+            is_channel_video: json_extract!(video, as_bool, ["isChannelVideo"]),
+            is_payment_required: json_extract!(video, as_bool, ["isPaymentRequired"]),
+        })
+    }
+}
@@ -0,0 +1,258 @@
+use super::details::VideoOwner;
+use crate::*;
+use std::time::Duration;
+
+/// Represents the information of a video obtained from the `getthumbinfo` API.
+/// See also [`video::thumbinfo`](super::thumbinfo).
+#[derive(Debug, Clone)]
+pub struct ThumbInfo {
+    /// The ID of the video.
+    pub id: VideoId,
+    /// The title of the video.
+    pub title: String,
+    /// The description of the video.
+    pub description: String,
+    /// The URL of the thumbnail.
+    pub thumbnail_url: String,
+    /// The date when the video was uploaded.
+    pub first_retrieve: DateTime<FixedOffset>,
+    /// The length of the video.
+    pub length: Duration,
+    /// The format of the video file, such as `mp4` or `flv`.
+    pub movie_type: String,
+    /// The size of the video file in bytes.
+    pub size_high: u64,
+    /// The size of the economy-mode video file in bytes.
+    pub size_low: u64,
+    /// The number of times the video has been viewed.
+    pub view_count: u64,
+    /// The number of comments on the video.
+    pub comment_count: u64,
+    /// The number of mylists the video is registered to.
+    pub mylist_count: u64,
+    /// The latest comments, joined with spaces.
+    pub last_res_body: String,
+    /// The URL of the watch page.
+    pub watch_url: String,
+    /// The type of the video, such as `video` or `mymemory`.
+    pub thumb_type: String,
+    /// Whether the video can be embedded in other sites.
+    pub embeddable: bool,
+    /// Whether the video cannot be played in live programs.
+    pub no_live_play: bool,
+    /// The tags of the video.
+    pub tags: Vec<ThumbInfoTag>,
+    /// The genre of the video. Not available for old videos.
+    pub genre: Option<String>,
+    /// The uploader of the video. `None` if the uploader has left niconico.
+    pub owner: Option<VideoOwner>,
+}
+impl ThumbInfo {
+    /// Fetches the information of the video. See also [`video::thumbinfo`](super::thumbinfo).
+    pub async fn fetch(session: &Session, id: &VideoId) -> Result<ThumbInfo> {
+        let url = format!("{}/api/getthumbinfo/{}", session.endpoints().ext, id);
+        let xml = session
+            .get_text(
+                &url,
+                RequestOptions {
+                    cookie_user_session: false,
+                    ..Default::default()
+                },
+            )
+            .await
+            .context("cannot fetch thumb info")?;
+        ThumbInfo::from_xml(&xml)
+    }
+    /// Parses a response of the `getthumbinfo` API.
+    ///
+    /// # Errors
+    /// - `Error::VideoDeleted` if the video has been deleted.
+    /// - `Error::VideoNotFound` if the video does not exist.
+    ///
+    /// # Examples
+    /// ```
+    /// # use niconico::{*, video::{details::VideoOwner, thumb_info::ThumbInfo}};
+    /// # use std::time::Duration;
+    /// let thumb = |length: &str| format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+    /// <nicovideo_thumb_response status="ok">
+    ///   <thumb>
+    ///     <video_id>sm9</video_id>
+    ///     <title>新・豪血寺一族 -煩悩解放 - レッツゴー！陰陽師</title>
+    ///     <description>レッツゴー！陰陽師（フルコーラスバージョン）</description>
+    ///     <thumbnail_url>https://nicovideo.cdn.nimg.jp/thumbnails/9/9</thumbnail_url>
+    ///     <first_retrieve>2007-03-06T00:33:00+09:00</first_retrieve>
+    ///     <length>{}</length>
+    ///     <movie_type>mp4</movie_type>
+    ///     <size_high>21138631</size_high>
+    ///     <size_low>17436492</size_low>
+    ///     <view_counter>20000000</view_counter>
+    ///     <comment_num>5000000</comment_num>
+    ///     <mylist_counter>200000</mylist_counter>
+    ///     <last_res_body>陰陽師 </last_res_body>
+    ///     <watch_url>https://www.nicovideo.jp/watch/sm9</watch_url>
+    ///     <thumb_type>video</thumb_type>
+    ///     <embeddable>1</embeddable>
+    ///     <no_live_play>0</no_live_play>
+    ///     <tags domain="jp">
+    ///       <tag category="1" lock="1">エンターテイメント</tag>
+    ///       <tag lock="1">陰陽師</tag>
+    ///     </tags>
+    ///     <genre>エンターテイメント</genre>
+    ///     <user_id>4</user_id>
+    ///     <user_nickname>中の</user_nickname>
+    ///     <user_icon_url>https://secure-dcdn.cdn.nimg.jp/nicoaccount/usericon/s/0/4.jpg</user_icon_url>
+    ///   </thumb>
+    /// </nicovideo_thumb_response>"#, length);
+    ///
+    /// let info = ThumbInfo::from_xml(&thumb("5:19"))?;
+    /// assert_eq!(info.id.as_str(), "sm9");
+    /// assert_eq!(info.length, Duration::from_secs(5 * 60 + 19));
+    /// assert_eq!(info.size_high, 21138631);
+    /// assert!(info.embeddable && !info.no_live_play);
+    /// assert_eq!(info.tags.len(), 2);
+    /// assert!(info.tags[0].is_category && info.tags[0].is_locked);
+    /// assert_eq!(info.genre.as_deref(), Some("エンターテイメント"));
+    /// match info.owner {
+    ///     Some(VideoOwner::User { user, .. }) => assert_eq!(user, User::UserId(4)),
+    ///     owner => panic!("unexpected owner: {:?}", owner),
+    /// }
+    ///
+    /// let info = ThumbInfo::from_xml(&thumb("1:05:19"))?;
+    /// assert_eq!(info.length, Duration::from_secs(3600 + 5 * 60 + 19));
+    /// assert!(ThumbInfo::from_xml(&thumb("5:1a")).is_err());
+    ///
+    /// let error = |code: &str| format!(r#"<nicovideo_thumb_response status="fail">
+    ///   <error><code>{}</code><description>error</description></error>
+    /// </nicovideo_thumb_response>"#, code);
+    /// let err = ThumbInfo::from_xml(&error("DELETED")).unwrap_err();
+    /// assert!(matches!(err.downcast_ref::<Error>(), Some(Error::VideoDeleted)));
+    /// let err = ThumbInfo::from_xml(&error("NOT_FOUND")).unwrap_err();
+    /// assert!(matches!(err.downcast_ref::<Error>(), Some(Error::VideoNotFound)));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn from_xml(xml: &str) -> Result<ThumbInfo> {
+        let doc = roxmltree::Document::parse(xml).context(Error::InvalidResponse)?;
+        let root = doc.root_element();
+
+        if root.attribute("status") != Some("ok") {
+            let code = find_child(root, "error")
+                .and_then(|error| find_child(error, "code"))
+                .and_then(|code| code.text())
+                .unwrap_or_default();
+            match code {
+                "DELETED" => bail!(Error::VideoDeleted),
+                "NOT_FOUND" => bail!(Error::VideoNotFound),
+                code => bail!(anyhow!("error code: `{}`", code).context(Error::InvalidResponse)),
+            }
+        }
+
+        let thumb = find_child(root, "thumb")
+            .context("`thumb` is missing")
+            .context(Error::InvalidResponse)?;
+
+        let tags = thumb
+            .children()
+            .filter(|n| n.has_tag_name("tags"))
+            .find(|n| n.attribute("domain").unwrap_or("jp") == "jp")
+            .map(|tags| {
+                tags.children()
+                    .filter(|n| n.has_tag_name("tag"))
+                    .map(|tag| ThumbInfoTag {
+                        name: tag.text().unwrap_or_default().to_owned(),
+                        is_locked: tag.attribute("lock") == Some("1"),
+                        is_category: tag.attribute("category") == Some("1"),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let owner = if let Ok(user_id) = child_text(thumb, "user_id") {
+            Some(VideoOwner::User {
+                user: User::UserId(user_id.parse().context(Error::InvalidResponse)?),
+                nickname: child_text(thumb, "user_nickname")?.to_owned(),
+                icon_url: child_text(thumb, "user_icon_url")?.to_owned(),
+            })
+        } else if let Ok(ch_id) = child_text(thumb, "ch_id") {
+            Some(VideoOwner::Channel {
+                id: ch_id.parse().context(Error::InvalidResponse)?,
+                name: child_text(thumb, "ch_name")?.to_owned(),
+                icon_url: child_text(thumb, "ch_icon_url")?.to_owned(),
+            })
+        } else {
+            None
+        };
+
+        Ok(ThumbInfo {
+            id: child_text(thumb, "video_id")?.parse()?,
+            title: child_text(thumb, "title")?.to_owned(),
+            description: child_text(thumb, "description")
+                .unwrap_or_default()
+                .to_owned(),
+            thumbnail_url: child_text(thumb, "thumbnail_url")?.to_owned(),
+            first_retrieve: DateTime::<FixedOffset>::parse_from_rfc3339(child_text(
+                thumb,
+                "first_retrieve",
+            )?)
+            .context(Error::InvalidResponse)?,
+            length: parse_length(child_text(thumb, "length")?)?,
+            movie_type: child_text(thumb, "movie_type")?.to_owned(),
+            size_high: child_u64(thumb, "size_high")?,
+            size_low: child_u64(thumb, "size_low")?,
+            view_count: child_u64(thumb, "view_counter")?,
+            comment_count: child_u64(thumb, "comment_num")?,
+            mylist_count: child_u64(thumb, "mylist_counter")?,
+            last_res_body: child_text(thumb, "last_res_body")
+                .unwrap_or_default()
+                .to_owned(),
+            watch_url: child_text(thumb, "watch_url")?.to_owned(),
+            thumb_type: child_text(thumb, "thumb_type")?.to_owned(),
+            embeddable: child_text(thumb, "embeddable")? == "1",
+            no_live_play: child_text(thumb, "no_live_play")? == "1",
+            tags,
+            genre: child_text(thumb, "genre").ok().map(|s| s.to_owned()),
+            owner,
+        })
+    }
+}
+
+/// Represents a tag of a video obtained from the `getthumbinfo` API.
+#[derive(Debug, Clone)]
+pub struct ThumbInfoTag {
+    /// The name of the tag.
+    pub name: String,
+    /// Whether the tag is locked by the uploader.
+    pub is_locked: bool,
+    /// Whether the tag is a category tag.
+    pub is_category: bool,
+}
+
+pub(crate) fn find_child<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+pub(crate) fn child_text<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> Result<&'a str> {
+    find_child(node, name)
+        .map(|n| n.text().unwrap_or_default())
+        .with_context(|| format!("`{}` is missing", name))
+        .context(Error::InvalidResponse)
+}
+fn child_u64(node: roxmltree::Node, name: &str) -> Result<u64> {
+    child_text(node, name)?
+        .parse()
+        .with_context(|| format!("`{}` is not a number", name))
+        .context(Error::InvalidResponse)
+}
+/// Parses a length such as `5:19` or `1:05:19`.
+fn parse_length(length: &str) -> Result<Duration> {
+    let mut secs = 0;
+    for part in length.split(':') {
+        let part: u64 = part
+            .parse()
+            .with_context(|| format!("invalid length: `{}`", length))
+            .context(Error::InvalidResponse)?;
+        secs = secs * 60 + part;
+    }
+    Ok(Duration::from_secs(secs))
+}
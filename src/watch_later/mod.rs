@@ -0,0 +1,112 @@
+//! Manages the watch later list (あとで見る) of the login user.
+
+use crate::*;
+use mylist::MylistSort;
+
+pub mod watch_later_item;
+
+/// Creates stream of the items of the watch later list.
+///
+/// The items can be sorted by the same keys as mylists.
+///
+/// # Errors
+/// The stream yields `Error::LoginRequired` if the session is not logged in.
+///
+/// # Examples
+/// ```no_run
+/// use futures::StreamExt;
+/// # use niconico::{*, mylist::MylistSort};
+/// # const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// # let mut session = Session::new(USER_AGENT, Language::Japanese);
+/// # session.set_cookie_user_session(&std::env::var("NICO_SID")?);
+/// watch_later::add(&session, &"sm9".parse()?, "watch tonight").await?;
+///
+/// let mut items = watch_later::stream(&session, MylistSort::default());
+/// while let Some(item) = items.next().await {
+///     let item = item?;
+///     println!("{} ({}): {}", item.video.title, item.added_at, item.memo);
+/// }
+///
+/// watch_later::clear_watched(&session).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn stream(session: &Session, sort: MylistSort) -> watch_later_item::WatchLaterStream<'_> {
+    watch_later_item::WatchLaterStream::new(session, sort)
+}
+
+/// Adds the video to the watch later list with the memo. See [`stream`] for examples.
+///
+/// # Errors
+/// `Error::LoginRequired` if the session is not logged in.
+pub async fn add(session: &Session, video: &VideoId, memo: &str) -> Result<()> {
+    let request = session
+        .post(&gen_url(session)?, request_options())
+        .form(&[("watchId", video.as_str()), ("memo", memo)]);
+    send(session, request)
+        .await
+        .context("cannot add the video to the watch later list")
+}
+/// Removes the items from the watch later list.
+///
+/// `item_ids` are [`WatchLaterItem::item_id`](watch_later_item::WatchLaterItem::item_id)s.
+///
+/// # Errors
+/// `Error::LoginRequired` if the session is not logged in.
+pub async fn remove_items(session: &Session, item_ids: &[u64]) -> Result<()> {
+    let item_ids = item_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    let request = session
+        .delete(&gen_url(session)?, request_options())
+        .query(&[("itemIds", item_ids)]);
+    send(session, request)
+        .await
+        .context("cannot remove the items from the watch later list")
+}
+/// Removes the items which the login user has watched. See [`stream`] for examples.
+///
+/// # Errors
+/// `Error::LoginRequired` if the session is not logged in.
+pub async fn clear_watched(session: &Session) -> Result<()> {
+    let request = session
+        .delete(&gen_url(session)?, request_options())
+        .query(&[("isWatched", "true")]);
+    send(session, request)
+        .await
+        .context("cannot remove the watched items from the watch later list")
+}
+
+async fn send(session: &Session, request: reqwest::RequestBuilder) -> Result<()> {
+    let json = session.send_json(request).await?;
+
+    let status = json_extract!(json, as_u64, ["meta"]["status"]);
+    match status {
+        200 | 201 => Ok(()),
+        401 => bail!(Error::LoginRequired),
+        _ => {
+            let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+            bail!(anyhow!("{} {}", status, error_code).context(Error::InvalidResponse))
+        }
+    }
+}
+
+fn request_options() -> RequestOptions {
+    RequestOptions {
+        header_x_frontend_id: true,
+        header_x_request_with: true,
+        ..Default::default()
+    }
+}
+
+fn gen_url(session: &Session) -> Result<String> {
+    ensure!(session.is_logged_in(), Error::LoginRequired);
+    Ok(format!(
+        "{}/v1/users/me/watch-later",
+        session.endpoints().nvapi
+    ))
+}
@@ -0,0 +1,156 @@
+use crate::*;
+use mylist::MylistSort;
+use std::collections::VecDeque;
+use video::summary::VideoSummary;
+
+/// Represents an item of the watch later list.
+#[derive(Debug, Clone)]
+pub struct WatchLaterItem {
+    /// The ID number of this item, which is unique in the list.
+    pub item_id: u64,
+    /// The video of this item.
+    pub video: VideoSummary,
+    /// The memo the login user wrote on this item.
+    pub memo: String,
+    /// The date when this item was added.
+    pub added_at: DateTime<FixedOffset>,
+    /// Whether the login user has watched the video since it was added.
+    pub is_watched: bool,
+}
+impl WatchLaterItem {
+    pub(crate) fn from_json(json: &serde_json::Value) -> Result<WatchLaterItem> {
+        Ok(WatchLaterItem {
+            item_id: json_extract!(json, as_u64, ["itemId"]),
+            video: VideoSummary::from_json(&json["video"])?,
+            memo: json_extract!(json, as_string, ["memo"]),
+            added_at: DateTime::<FixedOffset>::parse_from_rfc3339(json_extract!(
+                json,
+                as_str,
+                ["addedAt"]
+            ))
+            .context(Error::InvalidResponse)?,
+            is_watched: json_extract_optional!(json, as_bool, ["isWatched"]).unwrap_or_default(),
+        })
+    }
+}
+
+type FetchWatchLaterItemFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<(VecDeque<WatchLaterItem>, bool, usize)>> + 'a>>;
+
+/// Streams items of the watch later list.
+pub struct WatchLaterStream<'a> {
+    session: &'a Session,
+    sort: MylistSort,
+    future: Option<FetchWatchLaterItemFuture<'a>>,
+    buf: VecDeque<WatchLaterItem>,
+    is_finished: bool,
+    next_page: u32,
+    len: Option<usize>,
+}
+impl<'a> Stream for WatchLaterStream<'a> {
+    type Item = Result<WatchLaterItem>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        if self.buf.is_empty() && self.future.is_none() && !self.is_finished {
+            self.future = Some(Box::pin(Self::gen_future(
+                self.session,
+                self.sort,
+                self.next_page,
+            )));
+        }
+        if let Some(future) = self.future.as_mut() {
+            match future.as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => {
+                    self.future = None;
+                    match result {
+                        Ok((buf, is_finished, len)) => {
+                            self.buf = buf;
+                            self.is_finished = is_finished;
+                            self.next_page += 1;
+                            self.len = Some(len);
+                        }
+                        Err(err) => return Poll::Ready(Some(Err(err))),
+                    }
+                }
+            }
+        }
+
+        Poll::Ready(Ok(self.buf.pop_front()).transpose())
+    }
+}
+impl<'a> WatchLaterStream<'a> {
+    pub fn new(session: &Session, sort: MylistSort) -> WatchLaterStream<'_> {
+        WatchLaterStream {
+            session,
+            sort,
+            future: None,
+            buf: VecDeque::new(),
+            is_finished: false,
+            next_page: 1,
+            len: None,
+        }
+    }
+    async fn gen_future(
+        session: &'a Session,
+        sort: MylistSort,
+        page: u32,
+    ) -> Result<(VecDeque<WatchLaterItem>, bool, usize)> {
+        let url = gen_url(&session.endpoints().nvapi, sort, page);
+        let json = session
+            .get_json(
+                &url,
+                RequestOptions {
+                    header_x_frontend_id: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .context("cannot fetch watch later items")
+            .context(Error::InvalidResponse)?;
+
+        let status = json_extract!(json, as_u64, ["meta"]["status"]);
+        match status {
+            200 => (),
+            401 => bail!(Error::LoginRequired),
+            _ => {
+                let error_code = json_extract!(json, as_str, ["meta"]["errorCode"]);
+                bail!(anyhow!("{} {}", status, error_code).context(Error::InvalidResponse));
+            }
+        }
+
+        let mut items = VecDeque::new();
+        for item in json_extract!(json, as_array, ["data"]["watchLater"]["items"]) {
+            items.push_back(WatchLaterItem::from_json(item)?);
+        }
+
+        let len = json_extract!(json, as_u64, ["data"]["watchLater"]["totalCount"]) as usize;
+        let is_finished = !json_extract!(json, as_bool, ["data"]["watchLater"]["hasNext"]);
+
+        Ok((items, is_finished, len))
+    }
+    /// Fetches the number of the items.
+    pub async fn len(&mut self) -> Result<usize> {
+        // length is written in all response.
+        // if not received any response yet, do the first request.
+        if self.len.is_none() {
+            let (buf, is_finished, len) =
+                Self::gen_future(self.session, self.sort, self.next_page).await?;
+            self.buf = buf;
+            self.is_finished = is_finished;
+            self.next_page += 1;
+            self.len = Some(len);
+        }
+
+        Ok(self.len.unwrap())
+    }
+}
+
+fn gen_url(nvapi: &str, sort: MylistSort, page: u32) -> String {
+    format!(
+        "{}/v1/users/me/watch-later?pageSize=100&page={}&sortKey={}&sortOrder={}",
+        nvapi,
+        page,
+        sort.key.as_str(),
+        sort.order.as_str()
+    )
+}